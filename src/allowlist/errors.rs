@@ -0,0 +1,16 @@
+use thiserror::Error;
+use std::io;
+
+/// AllowlistError describes all errors that can occure in Allowlist.
+///
+#[derive(Error, Debug)]
+pub enum AllowlistError {
+    #[error("failed to read from file, {0}")]
+    ReadFailure(#[from] io::Error),
+    #[error("failed to parse yaml, {0}")]
+    YamlParsing(#[from] serde_yaml::Error),
+    #[error("failed to build allowlist, Regex failed with: {0}")]
+    RegexBuilderFailure(#[from] regex::Error),
+    #[error("failed to build allowlist, Glob failed with: {0}")]
+    GlobBuilderFailure(#[from] globset::Error),
+}
@@ -0,0 +1,116 @@
+pub mod errors;
+
+use errors::AllowlistError;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use openssl::sha::sha256;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_yaml::from_str as yaml_from_str;
+use std::collections::HashSet;
+use std::fs::read_to_string;
+use std::path::Path;
+use crate::lines::LinesEndsProvider;
+use crate::result::Secret;
+
+#[cfg(test)]
+mod mod_test;
+
+const IGNORE: &str = "rogue:ignore";
+const IGNORE_NEXT_LINE: &str = "-next-line";
+
+/// AllowlistSchema represents the allowlist yaml file.
+/// Regexes are matched against the raw result, paths are globs matched against the file relative to the scanned root,
+/// hashes are SHA-256 hex digests of the secret values and detectors are detector names.
+///
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Default)]
+pub struct AllowlistSchema {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    regexes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    paths: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    hashes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    detectors: Vec<String>,
+}
+
+/// Allowlist decides which findings are allowed to stay in the code and shall not be reported.
+///
+#[derive(Debug)]
+pub struct Allowlist {
+    regexes: Vec<Regex>,
+    paths: GlobSet,
+    hashes: HashSet<String>,
+    detectors: HashSet<String>,
+}
+
+impl Allowlist {
+    /// Reads Allowlist from yaml file.
+    ///
+    #[inline(always)]
+    pub fn read_from_yaml_file(path: &Path) -> Result<Self, AllowlistError> {
+        let yaml = read_to_string(path)?;
+        let schema: AllowlistSchema = yaml_from_str(&yaml)?;
+        schema.try_into()
+    }
+
+    /// Checks if the secret found in the file, given relative to the scanned root,
+    /// is allowed by any of the regexes, paths, hashes or detectors.
+    ///
+    #[inline(always)]
+    pub fn is_allowed(&self, secret: &Secret, file: &str) -> bool {
+        self.detectors.contains(&secret.detector_type.to_string())
+            || self.paths.is_match(file)
+            || secret.values().iter().any(|v| self.hashes.contains(&secret_hash(v)))
+            || self.regexes.iter().any(|r| r.is_match(&secret.raw_result))
+    }
+}
+
+impl TryFrom<AllowlistSchema> for Allowlist {
+    type Error = AllowlistError;
+
+    #[inline(always)]
+    fn try_from(schema: AllowlistSchema) -> Result<Self, Self::Error> {
+        let mut regexes = Vec::with_capacity(schema.regexes.len());
+        for regex in schema.regexes.iter() {
+            regexes.push(Regex::new(regex)?);
+        }
+        let mut paths = GlobSetBuilder::new();
+        for path in schema.paths.iter() {
+            paths.add(Glob::new(path)?);
+        }
+
+        Ok(Self {
+            regexes,
+            paths: paths.build()?,
+            hashes: schema.hashes.iter().map(|h| h.to_lowercase()).collect(),
+            detectors: schema.detectors.into_iter().collect(),
+        })
+    }
+}
+
+/// Calculates SHA-256 hex digest of the secret, used to allow exact secrets without storing them.
+///
+#[inline(always)]
+pub fn secret_hash(raw: &str) -> String {
+    hex::encode(sha256(raw.as_bytes()))
+}
+
+/// Resolves lines suppressed with inline comments.
+/// Line containing rogue:ignore is suppressed, line following the rogue:ignore-next-line is suppressed.
+///
+#[inline(always)]
+pub fn suppressed_lines(lines_ends: &impl LinesEndsProvider, s: &str) -> HashSet<usize> {
+    let mut lines = HashSet::new();
+    for (start, _) in s.match_indices(IGNORE) {
+        let Some(line) = lines_ends.get_line(start) else {
+            continue;
+        };
+        match s[start + IGNORE.len()..].starts_with(IGNORE_NEXT_LINE) {
+            true => lines.insert(line + 1),
+            false => lines.insert(line),
+        };
+    }
+
+    lines
+}
@@ -0,0 +1,317 @@
+use std::io::{self, copy, Cursor, Read, Write};
+use bzip2::read::MultiBzDecoder;
+use flate2::read::MultiGzDecoder;
+use tar::Archive;
+use zip::ZipArchive;
+use crate::result::SkipReason;
+
+#[cfg(test)]
+mod mod_test;
+
+const NESTED_SEPARATOR: &str = "!/";
+const MAX_COMPRESSION_LAYERS: usize = 4;
+const COMPRESSION_RATIO_FREE_BYTES: u64 = 1024 * 1024;
+const TAR_MAGIC: &[u8] = b"ustar";
+const TAR_MAGIC_OFFSET: usize = 257;
+const ISO_MAGIC: &[u8] = b"CD001";
+const ISO_MAGIC_OFFSET: usize = 0x8001;
+
+/// Default depth of nested archives, enough for a jar inside a war inside a compressed tar.
+///
+pub const DEFAULT_MAX_DEPTH: usize = 4;
+/// Default maximum size of a scanned file in bytes.
+///
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 50 * 1024 * 1024;
+/// Default maximum number of bytes decompressed from a single archive.
+///
+pub const DEFAULT_MAX_ARCHIVE_SIZE: u64 = 512 * 1024 * 1024;
+/// Default maximum ratio of decompressed to compressed size.
+///
+pub const DEFAULT_MAX_COMPRESSION_RATIO: u64 = 100;
+/// Default maximum number of entries read from a single archive including the nested archives.
+///
+pub const DEFAULT_MAX_ENTRIES: usize = 10_000;
+
+/// Limits protect the scanner from huge files and decompression bombs.
+/// Compression ratio is checked only when more than a megabyte is decompressed, small well compressed files are fine.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    pub max_file_size: u64,
+    pub max_archive_size: u64,
+    pub max_compression_ratio: u64,
+    pub max_entries: usize,
+    pub max_depth: usize,
+}
+
+impl Default for Limits {
+    #[inline(always)]
+    fn default() -> Self {
+        Self {
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            max_archive_size: DEFAULT_MAX_ARCHIVE_SIZE,
+            max_compression_ratio: DEFAULT_MAX_COMPRESSION_RATIO,
+            max_entries: DEFAULT_MAX_ENTRIES,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+}
+
+/// Kind is the format of the archive or the compressed stream recognised by the magic bytes.
+/// Seven zip, rar and iso are recognised but can't be opened, those are passed on as files.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Zip,
+    Tar,
+    Gzip,
+    Bzip2,
+    Xz,
+    Zstd,
+    SevenZip,
+    Rar,
+    Iso,
+}
+
+impl Kind {
+    /// Detects the kind from the magic bytes at the beginning of the content, jar, war, apk and aar are zip archives.
+    ///
+    #[inline(always)]
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            [b'P', b'K', 3, 4, ..] | [b'P', b'K', 5, 6, ..] | [b'P', b'K', 7, 8, ..] => Some(Self::Zip),
+            [0x1f, 0x8b, ..] => Some(Self::Gzip),
+            [b'B', b'Z', b'h', ..] => Some(Self::Bzip2),
+            [0xfd, b'7', b'z', b'X', b'Z', 0x00, ..] => Some(Self::Xz),
+            [0x28, 0xb5, 0x2f, 0xfd, ..] => Some(Self::Zstd),
+            [b'7', b'z', 0xbc, 0xaf, 0x27, 0x1c, ..] => Some(Self::SevenZip),
+            [b'R', b'a', b'r', b'!', 0x1a, 0x07, ..] => Some(Self::Rar),
+            _ if bytes.get(TAR_MAGIC_OFFSET..TAR_MAGIC_OFFSET + TAR_MAGIC.len()) == Some(TAR_MAGIC) => Some(Self::Tar),
+            _ if bytes.get(ISO_MAGIC_OFFSET..ISO_MAGIC_OFFSET + ISO_MAGIC.len()) == Some(ISO_MAGIC) => Some(Self::Iso),
+            _ => None,
+        }
+    }
+
+    #[inline(always)]
+    fn is_compression(&self) -> bool {
+        matches!(self, Self::Gzip | Self::Bzip2 | Self::Xz | Self::Zstd)
+    }
+}
+
+/// Extracted is the file found by the Extractor or the file skipped because it hit one of the limits.
+///
+#[derive(Debug, PartialEq, Eq)]
+pub enum Extracted {
+    File(String, Vec<u8>),
+    Skipped(String, SkipReason),
+}
+
+/// Budget is what remains of the limits shared by all nested archives of the extracted file.
+///
+#[derive(Debug)]
+struct Budget {
+    bytes: u64,
+    entries: usize,
+}
+
+/// Extractor walks archives recursively and passes every file that isn't an archive with its nested path.
+/// Nested path joins the archive path and the path inside of the archive with `!/`, like `outer.tar!/lib/a.jar!/config.properties`.
+/// Compressed streams are decompressed in place and don't add to the path nor to the depth.
+///
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Extractor {
+    limits: Limits,
+}
+
+impl Extractor {
+    #[inline(always)]
+    pub fn new(limits: Limits) -> Self {
+        Self { limits }
+    }
+
+    /// Extracts the content calling the function with each file found or skipped.
+    /// Content that isn't an archive and archives that fail to open are passed on as files.
+    ///
+    #[inline(always)]
+    pub fn extract(&self, path: &str, bytes: Vec<u8>, f: &mut impl FnMut(Extracted)) {
+        let mut budget = Budget { bytes: self.limits.max_archive_size, entries: self.limits.max_entries };
+        self.walk(path, bytes, 0, 0, &mut budget, f);
+    }
+
+    #[inline(always)]
+    fn walk(&self, path: &str, bytes: Vec<u8>, depth: usize, layers: usize, budget: &mut Budget, f: &mut impl FnMut(Extracted)) {
+        let kind = match Kind::detect(&bytes) {
+            Some(kind) => kind,
+            None => return self.file(path, bytes, f),
+        };
+        if kind.is_compression() {
+            if layers >= MAX_COMPRESSION_LAYERS {
+                return f(Extracted::Skipped(path.to_string(), SkipReason::Depth));
+            }
+            return match self.decompress(kind, &bytes, budget) {
+                Ok(decompressed) => self.walk(path, decompressed, depth, layers + 1, budget, f),
+                Err(Some(reason)) => f(Extracted::Skipped(path.to_string(), reason)),
+                Err(None) => self.file(path, bytes, f),
+            };
+        }
+        if matches!(kind, Kind::Zip | Kind::Tar) && depth >= self.limits.max_depth {
+            return f(Extracted::Skipped(path.to_string(), SkipReason::Depth));
+        }
+        match kind {
+            Kind::Zip => self.zip(path, bytes, depth, budget, f),
+            Kind::Tar => self.tar(path, bytes, depth, budget, f),
+            _ => self.file(path, bytes, f),
+        }
+    }
+
+    #[inline(always)]
+    fn file(&self, path: &str, bytes: Vec<u8>, f: &mut impl FnMut(Extracted)) {
+        match bytes.len() as u64 > self.limits.max_file_size {
+            true => f(Extracted::Skipped(path.to_string(), SkipReason::FileSize)),
+            false => f(Extracted::File(path.to_string(), bytes)),
+        }
+    }
+
+    /// Decompresses the stream within the budget, fails with the reason if the limit is hit or without it if the stream is broken.
+    ///
+    #[inline(always)]
+    fn decompress(&self, kind: Kind, bytes: &[u8], budget: &mut Budget) -> Result<Vec<u8>, Option<SkipReason>> {
+        let (cap, reason) = self.cap(bytes.len() as u64, budget);
+        let mut writer = CappedWriter { buf: Vec::new(), cap };
+        let result = match kind {
+            Kind::Gzip => copy(&mut MultiGzDecoder::new(bytes), &mut writer).map(|_| ()),
+            Kind::Bzip2 => copy(&mut MultiBzDecoder::new(bytes), &mut writer).map(|_| ()),
+            Kind::Xz => lzma_rs::xz_decompress(&mut Cursor::new(bytes), &mut writer).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string())),
+            Kind::Zstd => zstd::stream::copy_decode(bytes, &mut writer),
+            _ => return Err(None),
+        };
+        if writer.is_exceeded() {
+            return Err(Some(reason));
+        }
+        if result.is_err() {
+            return Err(None);
+        }
+        budget.bytes -= writer.buf.len() as u64;
+
+        Ok(writer.buf)
+    }
+
+    #[inline(always)]
+    fn zip(&self, path: &str, bytes: Vec<u8>, depth: usize, budget: &mut Budget, f: &mut impl FnMut(Extracted)) {
+        let mut zip = match ZipArchive::new(Cursor::new(bytes.as_slice())) {
+            Ok(zip) => zip,
+            Err(_) => return self.file(path, bytes, f),
+        };
+        for i in 0..zip.len() {
+            let Ok(mut file) = zip.by_index(i) else {
+                continue;
+            };
+            if file.is_dir() {
+                continue;
+            }
+            if budget.entries == 0 {
+                return f(Extracted::Skipped(path.to_string(), SkipReason::EntryCount));
+            }
+            budget.entries -= 1;
+            let nested = format!("{path}{NESTED_SEPARATOR}{}", file.name());
+            let (cap, reason) = self.cap(file.compressed_size(), budget);
+            if file.size() > cap {
+                f(Extracted::Skipped(nested, reason));
+                continue;
+            }
+            let mut content = Vec::new();
+            if file.by_ref().take(cap + 1).read_to_end(&mut content).is_err() {
+                continue;
+            }
+            if content.len() as u64 > cap {
+                f(Extracted::Skipped(nested, reason));
+                continue;
+            }
+            budget.bytes -= content.len() as u64;
+            drop(file);
+            self.walk(&nested, content, depth + 1, 0, budget, f);
+        }
+    }
+
+    #[inline(always)]
+    fn tar(&self, path: &str, bytes: Vec<u8>, depth: usize, budget: &mut Budget, f: &mut impl FnMut(Extracted)) {
+        let mut tar = Archive::new(bytes.as_slice());
+        let entries = match tar.entries() {
+            Ok(entries) => entries,
+            Err(_) => return self.file(path, bytes, f),
+        };
+        for file in entries {
+            let Ok(mut file) = file else {
+                continue;
+            };
+            if !file.header().entry_type().is_file() {
+                continue;
+            }
+            if budget.entries == 0 {
+                return f(Extracted::Skipped(path.to_string(), SkipReason::EntryCount));
+            }
+            budget.entries -= 1;
+            let name = match file.path() {
+                Ok(p) => p.to_string_lossy().to_string(),
+                Err(_) => continue,
+            };
+            let nested = format!("{path}{NESTED_SEPARATOR}{name}");
+            if file.size() > budget.bytes {
+                f(Extracted::Skipped(nested, SkipReason::ArchiveSize));
+                continue;
+            }
+            let mut content = Vec::new();
+            if file.by_ref().take(budget.bytes + 1).read_to_end(&mut content).is_err() {
+                continue;
+            }
+            if content.len() as u64 > budget.bytes {
+                f(Extracted::Skipped(nested, SkipReason::ArchiveSize));
+                continue;
+            }
+            budget.bytes -= content.len() as u64;
+            self.walk(&nested, content, depth + 1, 0, budget, f);
+        }
+    }
+
+    /// Returns the number of bytes that may be decompressed from the compressed size and the limit that caps it.
+    ///
+    #[inline(always)]
+    fn cap(&self, compressed: u64, budget: &Budget) -> (u64, SkipReason) {
+        let ratio_cap = compressed.saturating_mul(self.limits.max_compression_ratio).max(COMPRESSION_RATIO_FREE_BYTES);
+        match ratio_cap < budget.bytes {
+            true => (ratio_cap, SkipReason::CompressionRatio),
+            false => (budget.bytes, SkipReason::ArchiveSize),
+        }
+    }
+}
+
+/// CappedWriter collects the bytes until the cap is exceeded, then fails the write.
+///
+struct CappedWriter {
+    buf: Vec<u8>,
+    cap: u64,
+}
+
+impl CappedWriter {
+    #[inline(always)]
+    fn is_exceeded(&self) -> bool {
+        self.buf.len() as u64 > self.cap
+    }
+}
+
+impl Write for CappedWriter {
+    #[inline(always)]
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        if self.is_exceeded() {
+            return Err(io::Error::new(io::ErrorKind::OutOfMemory, "decompression limit exceeded"));
+        }
+        let allowed = (self.cap + 1).saturating_sub(self.buf.len() as u64).min(data.len() as u64) as usize;
+        self.buf.extend_from_slice(&data[..allowed]);
+        Ok(allowed)
+    }
+
+    #[inline(always)]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,12 @@
+use thiserror::Error;
+use std::io;
+
+/// BaselineError describes all errors that can occure in Baseline.
+///
+#[derive(Error, Debug)]
+pub enum BaselineError {
+    #[error("failed to read or write file, {0}")]
+    FileIoFailure(#[from] io::Error),
+    #[error("failed to parse json, {0}")]
+    JsonParsing(#[from] serde_json::Error),
+}
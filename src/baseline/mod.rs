@@ -0,0 +1,136 @@
+pub mod errors;
+
+use errors::BaselineError;
+use openssl::sha::Sha256;
+use serde::{Deserialize, Serialize};
+use serde_json::{from_str as json_from_str, to_string_pretty as to_json_string_pretty};
+use std::collections::HashSet;
+use std::fs::{read_to_string, write};
+use std::path::Path;
+use std::sync::Mutex;
+use crate::allowlist::secret_hash;
+use crate::result::Secret;
+
+#[cfg(test)]
+mod mod_test;
+
+/// BaselineEntry describes the finding accepted in the baseline.
+/// Fingerprint is built from the detector, the file relative to the scanned root, the hash of the secret and the content of the line,
+/// so it doesn't change when the line is shifted or the same tree is scanned from another directory.
+///
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct BaselineEntry {
+    pub fingerprint: String,
+    pub detector: String,
+    pub file: String,
+    pub secret_hash: String,
+}
+
+impl BaselineEntry {
+    /// Creates the entry of the secret found in the given line content of the file, given relative to the scanned root.
+    ///
+    #[inline(always)]
+    pub fn new(secret: &Secret, file: &str, context: &str) -> Self {
+        let detector = secret.detector_type.to_string();
+        let secret_hash = secret_hash(&secret.raw_result);
+        let mut hasher = Sha256::new();
+        for part in [detector.as_str(), file, secret_hash.as_str(), context.trim()] {
+            hasher.update(part.as_bytes());
+            hasher.update(&[0]);
+        }
+
+        Self {
+            fingerprint: hex::encode(hasher.finish()),
+            detector,
+            file: file.to_string(),
+            secret_hash,
+        }
+    }
+}
+
+/// Baseline holds findings that are known and shall not be reported again.
+///
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct Baseline {
+    findings: Vec<BaselineEntry>,
+    #[serde(skip)]
+    fingerprints: HashSet<String>,
+}
+
+impl Baseline {
+    /// Reads Baseline from json file.
+    ///
+    #[inline(always)]
+    pub fn read_from_json_file(path: &Path) -> Result<Self, BaselineError> {
+        let json = read_to_string(path)?;
+        let baseline: Baseline = json_from_str(&json)?;
+        Ok(Self::from(baseline.findings))
+    }
+
+    /// Writes Baseline to json file, entries are sorted to keep the file stable between runs.
+    ///
+    #[inline(always)]
+    pub fn write_to_json_file(&mut self, path: &Path) -> Result<(), BaselineError> {
+        self.findings.sort_by(|a, b| (&a.file, &a.fingerprint).cmp(&(&b.file, &b.fingerprint)));
+        write(path, to_json_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Checks if the baseline contains the fingerprint.
+    ///
+    #[inline(always)]
+    pub fn contains(&self, fingerprint: &str) -> bool {
+        self.fingerprints.contains(fingerprint)
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.findings.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.findings.is_empty()
+    }
+}
+
+impl From<Vec<BaselineEntry>> for Baseline {
+    #[inline(always)]
+    fn from(entries: Vec<BaselineEntry>) -> Self {
+        let mut fingerprints = HashSet::with_capacity(entries.len());
+        let mut findings = Vec::with_capacity(entries.len());
+        for entry in entries {
+            if fingerprints.insert(entry.fingerprint.clone()) {
+                findings.push(entry);
+            }
+        }
+
+        Self { findings, fingerprints }
+    }
+}
+
+/// Recorder collects baseline entries of the findings from many threads.
+///
+#[derive(Debug, Default)]
+pub struct Recorder {
+    entries: Mutex<Vec<BaselineEntry>>,
+}
+
+impl Recorder {
+    #[inline(always)]
+    pub fn record(&self, entry: BaselineEntry) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.push(entry);
+        }
+    }
+
+    /// Returns the baseline of all recorded entries.
+    ///
+    #[inline(always)]
+    pub fn baseline(&self) -> Baseline {
+        match self.entries.lock() {
+            Ok(entries) => Baseline::from(entries.clone()),
+            Err(_) => Baseline::default(),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+#[cfg(test)]
+mod mod_test;
+
+const SAMPLE_SIZE: usize = 8192;
+const MAX_CONTROL_PERCENT: usize = 10;
+const UTF16_LE_BOM: &[u8] = &[0xff, 0xfe];
+const UTF16_BE_BOM: &[u8] = &[0xfe, 0xff];
+const MAGIC: &[&[u8]] = &[
+    b"\x7fELF",
+    b"MZ",
+    &[0xfe, 0xed, 0xfa, 0xce],
+    &[0xfe, 0xed, 0xfa, 0xcf],
+    &[0xce, 0xfa, 0xed, 0xfe],
+    &[0xcf, 0xfa, 0xed, 0xfe],
+    &[0xca, 0xfe, 0xba, 0xbe],
+    b"\0asm",
+    b"dex\n",
+    b"!<arch>\n",
+    b"\x89PNG",
+    &[0xff, 0xd8, 0xff],
+    b"GIF8",
+    b"%PDF-",
+    b"SQLite format 3\0",
+    b"PK\x03\x04",
+    &[0x1f, 0x8b],
+    &[0xfd, b'7', b'z', b'X', b'Z', 0x00],
+    &[0x28, 0xb5, 0x2f, 0xfd],
+    &[b'7', b'z', 0xbc, 0xaf, 0x27, 0x1c],
+    b"Rar!\x1a\x07",
+];
+
+/// Minimum length of the printable run extracted from binary content.
+/// Longer than the default of strings(1), shorter runs can't hold a secret and only add noise.
+///
+pub const MIN_STRING_LENGTH: usize = 6;
+
+/// Class is the kind of the file content recognised by magic bytes, byte order mark or the heuristic.
+/// Content with NUL bytes or with many control characters in the leading sample is binary.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Class {
+    Text,
+    Utf16Le,
+    Utf16Be,
+    Binary,
+}
+
+impl Class {
+    #[inline(always)]
+    pub fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(UTF16_LE_BOM) {
+            return Self::Utf16Le;
+        }
+        if bytes.starts_with(UTF16_BE_BOM) {
+            return Self::Utf16Be;
+        }
+        if MAGIC.iter().any(|m| bytes.starts_with(m)) {
+            return Self::Binary;
+        }
+        let sample = &bytes[..bytes.len().min(SAMPLE_SIZE)];
+        if sample.contains(&0) {
+            return Self::Binary;
+        }
+        let control = sample.iter().filter(|b| **b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0c | 0x1b)).count();
+        if control * 100 > sample.len() * MAX_CONTROL_PERCENT {
+            return Self::Binary;
+        }
+
+        Self::Text
+    }
+}
+
+/// Returns the text of the content to inspect.
+/// Text that isn't valid UTF-8 is decoded lossy, UTF-16 with byte order mark is decoded.
+/// Binary content gives printable strings if those shall be extracted and nothing otherwise.
+///
+#[inline(always)]
+pub fn text(bytes: Vec<u8>, extract_strings: bool) -> Option<String> {
+    match Class::detect(&bytes) {
+        Class::Text => Some(match String::from_utf8(bytes) {
+            Ok(text) => text,
+            Err(e) => String::from_utf8_lossy(e.as_bytes()).to_string(),
+        }),
+        Class::Utf16Le => Some(utf16(&bytes[UTF16_LE_BOM.len()..], u16::from_le_bytes)),
+        Class::Utf16Be => Some(utf16(&bytes[UTF16_BE_BOM.len()..], u16::from_be_bytes)),
+        Class::Binary => extract_strings.then(|| strings(&bytes, MIN_STRING_LENGTH)),
+    }
+}
+
+/// Extracts printable ASCII runs and UTF-16LE runs of at least min length like strings(1), each run in its own line.
+///
+#[inline(always)]
+pub fn strings(bytes: &[u8], min: usize) -> String {
+    let mut runs: Vec<String> = Vec::new();
+    for run in bytes.split(|b| !is_printable(*b)) {
+        if run.len() >= min {
+            runs.push(String::from_utf8_lossy(run).to_string());
+        }
+    }
+    for alignment in 0..2 {
+        let mut run = String::new();
+        for pair in bytes.get(alignment..).unwrap_or_default().chunks_exact(2) {
+            if pair[1] == 0 && is_printable(pair[0]) {
+                run.push(pair[0] as char);
+                continue;
+            }
+            if run.len() >= min {
+                runs.push(run.clone());
+            }
+            run.clear();
+        }
+        if run.len() >= min {
+            runs.push(run);
+        }
+    }
+
+    runs.join("\n")
+}
+
+#[inline(always)]
+fn is_printable(b: u8) -> bool {
+    b == b'\t' || (0x20..0x7f).contains(&b)
+}
+
+#[inline(always)]
+fn utf16(bytes: &[u8], f: fn([u8; 2]) -> u16) -> String {
+    let units: Vec<u16> = bytes.chunks_exact(2).map(|c| f([c[0], c[1]])).collect();
+    String::from_utf16_lossy(&units)
+}
@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+/// DecoderError describes all errors that can occure in Decoders.
+///
+#[derive(Error, Debug)]
+pub enum DecoderError {
+    #[error("failed to build decoder, Regex failed with: {0}")]
+    RegexBuilderFailure(#[from] regex::Error),
+}
@@ -0,0 +1,136 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::DateTime;
+use crossbeam_channel::Sender;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::detectors::{Scanner, entropy::{score, Charset}};
+use crate::lines::LinesEndsProvider;
+use crate::reporter::Input;
+use crate::result::{DecoderType, DetectorType, Rule, Secret, Severity};
+use super::errors::DecoderError;
+
+#[cfg(test)]
+mod mod_test;
+
+const JWT_DETECTOR: &str = "JWT";
+const JWT_DESCRIPTION: &str = "JSON Web Token with decodable header and claims.";
+const JWT_GROUP: &str = "jwt";
+const JWT_SEVERITY: Severity = Severity::High;
+const JWT_REMEDIATION: &str = "Revoke the token or rotate the signing key if the token has not expired, then remove the token from the source.";
+const JWT_CANDIDATE: &str = r"[A-Za-z0-9_\-]{10,}\.[A-Za-z0-9_\-]{10,}\.[A-Za-z0-9_\-]*";
+const JWT_CLAIMS: [&str; 2] = ["iss", "sub"];
+
+/// JwtDecoder finds JSON Web Tokens, base64url decodes the header and the payload and extracts alg, iss, sub and exp.
+/// Token is reported only if its header is a JSON object with the alg field.
+///
+#[derive(Debug)]
+pub struct JwtDecoder {
+    candidate: Regex,
+}
+
+impl JwtDecoder {
+    #[inline(always)]
+    pub fn try_new() -> Result<Self, DecoderError> {
+        Ok(Self {
+            candidate: Regex::new(JWT_CANDIDATE)?,
+        })
+    }
+
+    /// Describes the decoder as the rule of reported findings.
+    ///
+    #[inline(always)]
+    pub fn rule(&self) -> Rule {
+        Rule {
+            id: JWT_DETECTOR.to_string(),
+            description: Some(JWT_DESCRIPTION.to_string()),
+            groups: vec![JWT_GROUP.to_string()],
+            severity: JWT_SEVERITY,
+            tags: Vec::new(),
+            remediation: Some(JWT_REMEDIATION.to_string()),
+            references: Vec::new(),
+        }
+    }
+}
+
+impl Scanner for JwtDecoder {
+    #[inline(always)]
+    fn scan(&self, line_ends: &impl LinesEndsProvider, s: &str, file: &str, branch: &str, sx: Sender<Option<Input>>) {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or_default();
+        for candidate in self.candidate.find_iter(s) {
+            let Some(metadata) = metadata(candidate.as_str(), now) else {
+                continue;
+            };
+            let _ = sx.send(Some(Input::Finding(Box::new(Secret {
+                detector_type: DetectorType::Builtin(JWT_DETECTOR.to_string()),
+                decoder_type: DecoderType::Jwt,
+                raw_result: candidate.as_str().to_string(),
+                score: score(candidate.as_str(), Some(&Charset::Base64Url)),
+                severity: JWT_SEVERITY,
+                tags: Vec::new(),
+                remediation: Some(JWT_REMEDIATION.to_string()),
+                references: Vec::new(),
+                branch: branch.to_string(),
+                file: file.to_string(),
+                line: line_ends.get_line(candidate.start()).unwrap_or_default(),
+                author: None,
+                email: None,
+                commit: None,
+                commit_time: None,
+                metadata: Some(metadata),
+                fingerprint: None,
+                context: None,
+            }))));
+        }
+    }
+}
+
+/// Returns metadata of the token or None if the token isn't a structurally valid JWT.
+/// Expiration time is formatted in RFC-3339 and compared against now given in seconds since the unix epoch.
+///
+#[inline(always)]
+fn metadata(token: &str, now: i64) -> Option<BTreeMap<String, String>> {
+    let mut segments = token.split('.');
+    let (Some(header), Some(payload)) = (segments.next(), segments.next()) else {
+        return None;
+    };
+    let header = decode_json_object(header)?;
+    let alg = header.get("alg")?.as_str()?;
+
+    let mut metadata = BTreeMap::new();
+    metadata.insert("alg".to_string(), alg.to_string());
+
+    let Some(claims) = decode_json_object(payload) else {
+        return Some(metadata);
+    };
+    for claim in JWT_CLAIMS.iter() {
+        let Some(value) = claims.get(*claim) else {
+            continue;
+        };
+        let value = match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        metadata.insert(claim.to_string(), value);
+    }
+    if let Some(exp) = claims.get("exp").and_then(|exp| exp.as_i64().or(exp.as_f64().map(|f| f as i64))) {
+        let formatted = match DateTime::from_timestamp(exp, 0) {
+            Some(date) => date.to_rfc3339(),
+            None => exp.to_string(),
+        };
+        metadata.insert("exp".to_string(), formatted);
+        metadata.insert("expired".to_string(), (exp <= now).to_string());
+    }
+
+    Some(metadata)
+}
+
+#[inline(always)]
+fn decode_json_object(segment: &str) -> Option<Map<String, Value>> {
+    let decoded = URL_SAFE_NO_PAD.decode(segment.trim_end_matches('=')).ok()?;
+    match serde_json::from_slice(&decoded).ok()? {
+        Value::Object(object) => Some(object),
+        _ => None,
+    }
+}
@@ -0,0 +1,148 @@
+pub mod errors;
+pub mod jwt;
+
+use base64::{Engine, engine::general_purpose::{STANDARD_NO_PAD, URL_SAFE_NO_PAD}};
+use data_encoding::BASE32_NOPAD;
+use errors::DecoderError;
+use regex::Regex;
+use std::collections::HashSet;
+use crate::result::DecoderType;
+
+#[cfg(test)]
+mod mod_test;
+
+const MIN_ENCODED_LEN: usize = 16;
+const MIN_DECODED_LEN: usize = 6;
+const HEX_RUN: &str = r"[0-9a-fA-F]{16,}";
+const BASE32_RUN: &str = r"[A-Z2-7]{16,}={0,6}";
+const BASE58_RUN: &str = r"[1-9A-HJ-NP-Za-km-z]{16,}";
+const BASE64_RUN: &str = r"[A-Za-z0-9+/_\-]{16,}={0,2}";
+const BASE85_RUN: &str = r"<~([!-uz\s]+)~>";
+
+/// Decoded is the text decoded from the encoded run found in the given line of the input.
+/// Line is counted from 1.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Decoded {
+    pub decoder_type: DecoderType,
+    pub data: String,
+    pub line: usize,
+}
+
+/// Decoders finds encoded runs in the input and decodes them to the printable text.
+/// Runs that do not decode to the printable text are dropped as they cannot be scanned for secrets.
+/// Base85 runs are recognised only in Ascii85 delimiters <~ ~>, otherwise almost any text is a valid base85.
+///
+#[derive(Debug)]
+pub struct Decoders {
+    runs: Vec<(DecoderType, Regex)>,
+}
+
+impl Decoders {
+    #[inline(always)]
+    pub fn try_new() -> Result<Self, DecoderError> {
+        Ok(Self {
+            runs: vec![
+                (DecoderType::Hex, Regex::new(HEX_RUN)?),
+                (DecoderType::Base32, Regex::new(BASE32_RUN)?),
+                (DecoderType::Base58, Regex::new(BASE58_RUN)?),
+                (DecoderType::Base64, Regex::new(BASE64_RUN)?),
+                (DecoderType::Base85, Regex::new(BASE85_RUN)?),
+            ],
+        })
+    }
+
+    /// Decodes all the encoded runs of the input, each decoded text is returned once per line.
+    ///
+    #[inline(always)]
+    pub fn decode(&self, s: &str) -> Vec<Decoded> {
+        let mut decoded: Vec<Decoded> = Vec::new();
+        let mut seen: HashSet<(usize, String)> = HashSet::new();
+        for (idx, line) in s.lines().enumerate() {
+            if line.len() < MIN_ENCODED_LEN {
+                continue;
+            }
+            for (decoder_type, run) in self.runs.iter() {
+                for captures in run.captures_iter(line) {
+                    let Some(encoded) = captures.get(1).or(captures.get(0)) else {
+                        continue;
+                    };
+                    let Some(data) = decode_run(decoder_type, encoded.as_str()).and_then(printable) else {
+                        continue;
+                    };
+                    if !seen.insert((idx, data.clone())) {
+                        continue;
+                    }
+                    decoded.push(Decoded { decoder_type: decoder_type.clone(), data, line: idx + 1 });
+                }
+            }
+        }
+
+        decoded
+    }
+}
+
+#[inline(always)]
+fn decode_run(decoder_type: &DecoderType, encoded: &str) -> Option<Vec<u8>> {
+    match decoder_type {
+        DecoderType::Hex => hex::decode(encoded).ok(),
+        DecoderType::Base32 => BASE32_NOPAD.decode(encoded.trim_end_matches('=').as_bytes()).ok(),
+        DecoderType::Base58 => bs58::decode(encoded).into_vec().ok(),
+        DecoderType::Base64 => {
+            let encoded = encoded.trim_end_matches('=');
+            STANDARD_NO_PAD.decode(encoded).or_else(|_| URL_SAFE_NO_PAD.decode(encoded)).ok()
+        },
+        DecoderType::Base85 => decode_ascii85(encoded),
+        _ => None,
+    }
+}
+
+/// Decodes Ascii85 without delimiters, returns None if the input isn't a valid Ascii85.
+///
+#[inline(always)]
+fn decode_ascii85(encoded: &str) -> Option<Vec<u8>> {
+    let mut decoded: Vec<u8> = Vec::with_capacity(encoded.len() * 4 / 5);
+    let mut group: u64 = 0;
+    let mut count = 0;
+    for c in encoded.bytes() {
+        match c {
+            b'z' if count == 0 => decoded.extend_from_slice(&[0; 4]),
+            b'!'..=b'u' => {
+                group = group * 85 + (c - b'!') as u64;
+                count += 1;
+                if count == 5 {
+                    decoded.extend_from_slice(&u32::try_from(group).ok()?.to_be_bytes());
+                    group = 0;
+                    count = 0;
+                }
+            },
+            c if c.is_ascii_whitespace() => (),
+            _ => return None,
+        }
+    }
+    match count {
+        0 => (),
+        1 => return None,
+        _ => {
+            for _ in count..5 {
+                group = group * 85 + 84;
+            }
+            decoded.extend_from_slice(&u32::try_from(group).ok()?.to_be_bytes()[..count - 1]);
+        },
+    };
+
+    Some(decoded)
+}
+
+#[inline(always)]
+fn printable(decoded: Vec<u8>) -> Option<String> {
+    if decoded.len() < MIN_DECODED_LEN {
+        return None;
+    }
+    let decoded = String::from_utf8(decoded).ok()?;
+    if decoded.chars().any(|c| c.is_control() && !c.is_ascii_whitespace()) {
+        return None;
+    }
+
+    Some(decoded)
+}
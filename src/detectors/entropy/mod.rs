@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use super::errors::DetectorError;
+
+#[cfg(test)]
+mod mod_test;
+
+const HEX: &str = "0123456789abcdefABCDEF";
+const NUMERIC: &str = "0123456789";
+const ALPHANUMERIC: &str = "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const BASE64: &str = "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ+/=";
+const BASE64_URL: &str = "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ-_=";
+const LOWER_CASE_SIZE: usize = 26;
+const UPPER_CASE_SIZE: usize = 26;
+const DIGITS_SIZE: usize = 10;
+const SYMBOLS_SIZE: usize = 33;
+const CONFIDENT_LEN: usize = 16;
+const PLACEHOLDER_PENALTY: f64 = 0.1;
+const PLACEHOLDERS: [&str; 10] = [
+    "example", "xxxx", "****", "...", "<", "your_", "your-", "placeholder", "changeme", "dummy",
+];
+
+/// Charset restricts characters the secret value can consist of.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum Charset {
+    Hex,
+    Numeric,
+    Alphanumeric,
+    Base64,
+    Base64Url,
+}
+
+impl TryFrom<&str> for Charset {
+    type Error = DetectorError;
+
+    #[inline(always)]
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "hex" => Ok(Self::Hex),
+            "numeric" => Ok(Self::Numeric),
+            "alphanumeric" => Ok(Self::Alphanumeric),
+            "base64" => Ok(Self::Base64),
+            "base64url" => Ok(Self::Base64Url),
+            _ => Err(DetectorError::TryIntoError(format!("unexpected charset {s}, allowed are: hex, numeric, alphanumeric, base64, base64url"))),
+        }
+    }
+}
+
+impl Charset {
+    #[inline(always)]
+    fn chars(&self) -> &'static str {
+        match self {
+            Self::Hex => HEX,
+            Self::Numeric => NUMERIC,
+            Self::Alphanumeric => ALPHANUMERIC,
+            Self::Base64 => BASE64,
+            Self::Base64Url => BASE64_URL,
+        }
+    }
+
+    /// Checks if all characters of the value belong to the charset.
+    ///
+    #[inline(always)]
+    pub fn contains(&self, value: &str) -> bool {
+        let chars = self.chars();
+        value.chars().all(|c| chars.contains(c))
+    }
+
+    /// Returns number of distinct characters in the charset, case insensitive hex counts as 16.
+    ///
+    #[inline(always)]
+    fn size(&self) -> usize {
+        match self {
+            Self::Hex => 16,
+            _ => self.chars().len(),
+        }
+    }
+}
+
+/// Calculates Shannon entropy of the value in bits per character.
+///
+#[inline(always)]
+pub fn shannon(value: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    let mut len = 0;
+    for c in value.chars() {
+        *counts.entry(c).or_insert(0) += 1;
+        len += 1;
+    }
+    if len == 0 {
+        return 0.0;
+    }
+
+    counts.values().fold(0.0, |entropy, count| {
+        let p = *count as f64 / len as f64;
+        entropy - p * p.log2()
+    })
+}
+
+/// Scores confidence that the value is a real secret in range from 0 to 1.
+/// Entropy is normalized by the maximum entropy of the charset, or the alphabet guessed from the value if charset isn't given.
+/// Short values and values looking like placeholders, e.g. EXAMPLEKEY or xxxxxxxx, score lower.
+///
+#[inline(always)]
+pub fn score(value: &str, charset: Option<&Charset>) -> f64 {
+    let alphabet = match charset {
+        Some(charset) => charset.size(),
+        None => guess_alphabet_size(value),
+    };
+    if alphabet < 2 {
+        return 0.0;
+    }
+    let len = value.chars().count();
+    let max_entropy = (alphabet as f64).log2().min((len as f64).log2());
+    if max_entropy <= 0.0 {
+        return 0.0;
+    }
+    let mut score = (shannon(value) / max_entropy).min(1.0);
+    score *= (len as f64 / CONFIDENT_LEN as f64).min(1.0);
+    if is_placeholder(value) {
+        score *= PLACEHOLDER_PENALTY;
+    }
+
+    (score * 100.0).round() / 100.0
+}
+
+#[inline(always)]
+fn guess_alphabet_size(value: &str) -> usize {
+    let mut size = 0;
+    if value.chars().any(|c| c.is_ascii_lowercase()) {
+        size += LOWER_CASE_SIZE;
+    }
+    if value.chars().any(|c| c.is_ascii_uppercase()) {
+        size += UPPER_CASE_SIZE;
+    }
+    if value.chars().any(|c| c.is_ascii_digit()) {
+        size += DIGITS_SIZE;
+    }
+    if value.chars().any(|c| !c.is_ascii_alphanumeric()) {
+        size += SYMBOLS_SIZE;
+    }
+
+    size
+}
+
+#[inline(always)]
+fn is_placeholder(value: &str) -> bool {
+    let value = value.to_lowercase();
+    PLACEHOLDERS.iter().any(|p| value.contains(p))
+}
@@ -10,6 +10,8 @@ pub enum DetectorError {
     ReadFailure(#[from] io::Error),
     #[error("failed to parse yaml, {0}")]
     YamlParsing(#[from] serde_yaml::Error),
+    #[error("failed to parse json, {0}")]
+    JsonParsing(#[from] serde_json::Error),
     #[error("failed to build scanner, Aho Corasick failed with: {0}")]
     AhoCorasickFailure(#[from] BuildError),
     #[error("failed to build scanner, Regex failed with: {0}")]
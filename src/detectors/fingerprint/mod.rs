@@ -3,19 +3,27 @@
 // To get better results it is required to create larger population of fingerprints from redimed secrets.
 
 use std::collections::HashMap;
+use std::fs::read_to_string;
+use std::path::Path;
 use std::usize;
 
 use super::Scanner;
+use super::entropy::score;
 use super::errors::DetectorError;
 use crossbeam_channel::Sender;
 use serde::{Deserialize, Serialize};
 use meansd::MeanSD;
-use crate::result::{DecoderType, DetectorType};
+use crate::result::{DecoderType, DetectorType, Rule, Severity};
 use crate::{reporter::Input, result::Secret};
 use crate::lines::LinesEndsProvider;
 use tlsh::{Tlsh, Version, BucketKind, ChecksumKind, TlshBuilder};
+use quartiles::has_third_quartile;
+
+mod quartiles;
 
 const MIN_BYTES_LEN: usize = 50;
+const FINGERPRINT_GROUP: &str = "fingerprint";
+const WINDOW_STEPS: usize = 8;
 
 #[inline(always)]
 fn new_tlsh_hash<'a>(s: &'a str) -> Result<Tlsh, DetectorError>  {
@@ -28,20 +36,38 @@ fn new_tlsh_hash<'a>(s: &'a str) -> Result<Tlsh, DetectorError>  {
     if buf.len() < MIN_BYTES_LEN {
         return Err(DetectorError::FingerprintBuilderFailure(format!("expected min {} bytes, got {}", MIN_BYTES_LEN, buf.len())));
     }
-    builder.update(&buf);
-    match builder.build() {
-        Ok(h) => Ok(h),
-        Err(e) => Err(DetectorError::FingerprintBuilderFailure(e.to_string())),
+    // Builder panics on data it finds no third quartile for, like a long run of the same character.
+    if !has_third_quartile(buf) {
+        return Err(DetectorError::FingerprintBuilderFailure("data is too uniform to hash".to_string()));
     }
+    builder.update(&buf);
+    builder.build().map_err(|e| DetectorError::FingerprintBuilderFailure(e.to_string()))
 }
 
+/// SecretSuspectsData holds redeemed secrets of the same kind the fingerprints are created from.
+/// Suspects can be read from YAML or JSON file with the list of suspects.
+///
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SecretSuspectsData {
     pub name: String,
     pub secrets_for_fingerprint: Vec<String>,
+    #[serde(default)]
     pub include_length_diff: bool,
 }
 
+impl SecretSuspectsData {
+    /// Reads suspects from the file, files with json extension are parsed as JSON, other files as YAML.
+    ///
+    #[inline(always)]
+    pub fn read_from_file(path: &Path) -> Result<Vec<Self>, DetectorError> {
+        let content = read_to_string(path)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(e) if e.eq_ignore_ascii_case("json") => Ok(serde_json::from_str(&content)?),
+            _ => Ok(serde_yaml::from_str(&content)?),
+        }
+    }
+}
+
 #[derive(Debug)]
 struct Evidence {
     name: String,
@@ -53,6 +79,16 @@ struct Evidence {
     include_length_diff: bool,
 }
 
+impl Evidence {
+    /// Returns the distance of the data to the closest fingerprint, or none if the data cannot be hashed.
+    ///
+    #[inline(always)]
+    fn distance(&self, s: &str) -> Option<usize> {
+        let tlsh = new_tlsh_hash(s).ok()?;
+        self.fingerprints.iter().map(|f| f.diff(&tlsh, self.include_length_diff)).min()
+    }
+}
+
 /// Lab offers TLSH fingerprint comparsion for secrets similarity detection.
 ///
 #[derive(Debug)]
@@ -119,6 +155,21 @@ impl Lab {
 
         Ok(Self{ evidences })
     }
+
+    /// Describes each fingerprinted suspect as the rule of reported findings.
+    ///
+    #[inline(always)]
+    pub fn rules(&self) -> Vec<Rule> {
+        self.evidences
+            .iter()
+            .map(|evidence| Rule {
+                id: evidence.name.clone(),
+                description: Some(format!("Similar to {} fingerprinted secrets of {}.", evidence.fingerprints.len(), evidence.name)),
+                groups: vec![FINGERPRINT_GROUP.to_string()],
+                ..Default::default()
+            })
+            .collect()
+    }
 }
 
 impl Scanner for Lab {
@@ -155,46 +206,99 @@ where
         Self {s, file, branch, scanner, line_ends, sx}
     }
 
+    /// Slides the window as wide as the fingerprinted secrets over the data by the eighth of its width,
+    /// the similar windows are then narrowed down byte by byte.
+    /// Windows too uniform to be hashed are skipped, of the overlapping similar windows the closest one is reported.
+    ///
     #[inline(always)]
     fn detect(&'a mut self) {
         for evidance in self.scanner.evidences.iter() {
-            let mut start: usize = 0;
-            let mut finish: usize = evidance.size_mean + evidance.size_stdev + 1;
-            finish = if finish > self.s.len() { self.s.len() } else { finish };
+            let width = (evidance.size_mean + evidance.size_stdev + 1).min(self.s.len());
+            let max_dist = evidance.distance_mean + evidance.distance_stdev;
+            let step = (width / WINDOW_STEPS).max(1);
 
             let mut unique_secrets: HashMap<String, usize> = HashMap::new();
-            'slicer: while finish <= self.s.len() {
-                let s = &self.s[start..finish];
-                let Ok(tlsh) = new_tlsh_hash(s) else {
-                    return;
+            let mut closest: Option<(usize, usize, &str)> = None;
+            for start in (0..=self.s.len() - width).step_by(step) {
+                let Some(s) = self.s.get(start..start + width) else {
+                    continue;
                 };
-                let max_dist = evidance.distance_mean + evidance.distance_stdev;
-                for fingerprint in evidance.fingerprints.iter() {
-                    let distance = fingerprint.diff(&tlsh, evidance.include_length_diff);
-                    if distance <  max_dist {
-                        if let Some(_) = unique_secrets.insert(s.to_string(), start) {
-                            start += 1;
-                            finish += start;
-                            continue 'slicer;
-                        };
-                    }
+                let Some(distance) = evidance.distance(s) else {
+                    continue;
+                };
+                if distance >= max_dist {
+                    continue;
                 }
-                start += 1;
-                finish += start;
+                match closest {
+                    Some((closest_distance, closest_start, closest_s)) if start < closest_start + closest_s.len() => {
+                        if distance < closest_distance {
+                            closest = Some((distance, start, s));
+                        }
+                    },
+                    Some(found) => {
+                        let (closest_start, closest_s) = self.closest_around(evidance, step, found);
+                        insert_trimmed(&mut unique_secrets, closest_s, closest_start);
+                        closest = Some((distance, start, s));
+                    },
+                    None => closest = Some((distance, start, s)),
+                };
+            }
+            if let Some(found) = closest {
+                let (closest_start, closest_s) = self.closest_around(evidance, step, found);
+                insert_trimmed(&mut unique_secrets, closest_s, closest_start);
             }
             for (finding, start) in unique_secrets {
-                let _ = self.sx.send(Some(Input::Finding(Secret {
-                    detector_type: DetectorType::Unique(evidance.name.to_string()),
+                let _ = self.sx.send(Some(Input::Finding(Box::new(Secret {
+                    detector_type: DetectorType::Fingerprint(evidance.name.to_string()),
                     decoder_type: DecoderType::Plane,
+                    score: score(&finding, None),
+                    severity: Severity::default(),
+                    tags: Vec::new(),
+                    remediation: None,
+                    references: Vec::new(),
                     raw_result: finding,
                     branch: self.branch.to_string(),
                     file: self.file.to_string(),
                     line: self.line_ends.get_line(start).unwrap_or_default(),
                     author: None,
-                })));
+                    email: None,
+                    commit: None,
+                    commit_time: None,
+                    metadata: None,
+                    fingerprint: None,
+                    context: None,
+                }))));
             }
         }
     }
+
+    /// Slides the window by one byte within the step around the found window and returns the closest one,
+    /// so the reported finding starts where the secret does and not where the step happened to land.
+    ///
+    #[inline(always)]
+    fn closest_around(&self, evidance: &Evidence, step: usize, (distance, start, s): (usize, usize, &'a str)) -> (usize, &'a str) {
+        let width = s.len();
+        let mut closest = (distance, start, s);
+        for start in start.saturating_sub(step - 1)..=(start + step - 1).min(self.s.len() - width) {
+            let Some(s) = self.s.get(start..start + width) else {
+                continue;
+            };
+            match evidance.distance(s) {
+                Some(distance) if distance < closest.0 => closest = (distance, start, s),
+                _ => (),
+            };
+        }
+
+        (closest.1, closest.2)
+    }
+}
+
+/// Inserts the finding without surrounding whitespaces, so the finding starts at the line of its first character.
+///
+#[inline(always)]
+fn insert_trimmed(unique_secrets: &mut HashMap<String, usize>, s: &str, start: usize) {
+    let trimmed = s.trim_start();
+    unique_secrets.entry(trimmed.trim_end().to_string()).or_insert(start + s.len() - trimmed.len());
 }
 
 #[cfg(test)]
@@ -0,0 +1,124 @@
+// Mirrors the bucket counting and the quartile search of the tlsh crate builder for 128 buckets,
+// so the data the builder cannot hash is rejected before the builder panics on it.
+
+const BUCKET_COUNT: usize = 128;
+const WINDOW_SIZE: usize = 5;
+
+const V_TABLE: [u8; 256] = [
+    1, 87, 49, 12, 176, 178, 102, 166, 121, 193, 6, 84, 249, 230, 44, 163, 14, 197, 213, 181, 161,
+    85, 218, 80, 64, 239, 24, 226, 236, 142, 38, 200, 110, 177, 104, 103, 141, 253, 255, 50, 77,
+    101, 81, 18, 45, 96, 31, 222, 25, 107, 190, 70, 86, 237, 240, 34, 72, 242, 20, 214, 244, 227,
+    149, 235, 97, 234, 57, 22, 60, 250, 82, 175, 208, 5, 127, 199, 111, 62, 135, 248, 174, 169,
+    211, 58, 66, 154, 106, 195, 245, 171, 17, 187, 182, 179, 0, 243, 132, 56, 148, 75, 128, 133,
+    158, 100, 130, 126, 91, 13, 153, 246, 216, 219, 119, 68, 223, 78, 83, 88, 201, 99, 122, 11, 92,
+    32, 136, 114, 52, 10, 138, 30, 48, 183, 156, 35, 61, 26, 143, 74, 251, 94, 129, 162, 63, 152,
+    170, 7, 115, 167, 241, 206, 3, 150, 55, 59, 151, 220, 90, 53, 23, 131, 125, 173, 15, 238, 79,
+    95, 89, 16, 105, 137, 225, 224, 217, 160, 37, 123, 118, 73, 2, 157, 46, 116, 9, 145, 134, 228,
+    207, 212, 202, 215, 69, 229, 27, 188, 67, 124, 168, 252, 42, 4, 29, 108, 21, 247, 19, 205, 39,
+    203, 233, 40, 186, 147, 198, 192, 155, 33, 164, 191, 98, 204, 165, 180, 117, 76, 140, 36, 210,
+    172, 41, 54, 159, 8, 185, 232, 113, 196, 231, 47, 146, 120, 51, 65, 28, 144, 254, 221, 93, 189,
+    194, 139, 112, 43, 71, 109, 184, 209,
+];
+
+/// Tells if the builder finds the non zero third quartile of the buckets counted from the data,
+/// the builder panics when the third quartile is zero instead of returning an error.
+///
+#[inline(always)]
+pub(super) fn has_third_quartile(data: &[u8]) -> bool {
+    third_quartile(&mut buckets(data)) != 0
+}
+
+#[inline(always)]
+fn pearson_hash(salt: u8, i: u8, j: u8, k: u8) -> u8 {
+    [salt, i, j, k].iter().fold(0, |h, b| V_TABLE[(h ^ b) as usize])
+}
+
+/// Counts the triplets of each window of the data the same way the builder does.
+///
+#[inline(always)]
+fn buckets(data: &[u8]) -> Vec<u32> {
+    let mut buckets = vec![0; 256];
+    for window in data.windows(WINDOW_SIZE) {
+        let [a, b, c, d, e] = [window[4], window[3], window[2], window[1], window[0]];
+        for (salt, i, j, k) in [(2, a, b, c), (3, a, b, d), (5, a, c, d), (7, a, c, e), (11, a, b, e), (13, a, d, e)] {
+            buckets[pearson_hash(salt, i, j, k) as usize] += 1;
+        }
+    }
+    buckets.truncate(BUCKET_COUNT);
+    buckets
+}
+
+/// Finds the third quartile with the partial quick sort of the builder, including its shortcuts,
+/// as the builder reports zero whenever the median is found before any partition above it.
+///
+#[inline(always)]
+fn third_quartile(buckets: &mut [u32]) -> u32 {
+    let quartile = BUCKET_COUNT >> 2;
+    let p2 = 2 * quartile - 1;
+    let p3 = p2 + quartile;
+    let end = p3 + quartile;
+
+    let mut shortcut_high = Vec::new();
+    let (mut low, mut high) = (0, end);
+    loop {
+        let pivot = partition(buckets, low, high);
+        if pivot > p2 {
+            high = pivot - 1;
+            shortcut_high.push(pivot);
+        } else if pivot < p2 {
+            low = pivot + 1;
+        } else {
+            break;
+        }
+    }
+
+    let mut high = end;
+    for low in shortcut_high {
+        if low < p3 {
+            let mut low = low;
+            return loop {
+                let pivot = partition(buckets, low, high);
+                if pivot > p3 {
+                    high = pivot - 1;
+                } else if pivot < p3 {
+                    low = pivot + 1;
+                } else {
+                    break buckets[p3];
+                }
+            };
+        } else if low > p3 {
+            high = low;
+        } else {
+            return buckets[p3];
+        }
+    }
+
+    0
+}
+
+#[inline(always)]
+fn partition(buckets: &mut [u32], low: usize, high: usize) -> usize {
+    if low == high {
+        return low;
+    }
+    if low + 1 == high {
+        if buckets[low] > buckets[high] {
+            buckets.swap(low, high);
+        }
+        return low;
+    }
+
+    let (mut result, pivot) = (low, (low + high) >> 1);
+    let val = buckets[pivot];
+    buckets.swap(pivot, high);
+    for i in low..high {
+        if buckets[i] < val {
+            buckets.swap(i, result);
+            result += 1;
+        }
+    }
+    buckets[high] = buckets[result];
+    buckets[result] = val;
+
+    result
+}
@@ -0,0 +1,526 @@
+use crossbeam_channel::Sender;
+use crate::lines::LinesEndsProvider;
+use crate::reporter::Input;
+use crate::structured::Entry;
+use super::Scanner;
+use super::regex::Pattern;
+
+#[cfg(test)]
+mod mod_test;
+
+/// Maximum length of the character literal, longer quoted runs are taken for Rust lifetimes.
+///
+const MAX_CHAR_LITERAL_LEN: usize = 12;
+
+/// Language of the source file recognised by the file extension.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    Go,
+    Python,
+    JavaScript,
+    Java,
+    CSharp,
+    Shell,
+}
+
+impl Language {
+    #[inline(always)]
+    pub fn detect(file: &str) -> Option<Self> {
+        let name = file.rsplit(['/', '\\']).next().unwrap_or(file).to_lowercase();
+        let (_, extension) = name.rsplit_once('.')?;
+        match extension {
+            "rs" => Some(Self::Rust),
+            "go" => Some(Self::Go),
+            "py" | "pyw" => Some(Self::Python),
+            "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" => Some(Self::JavaScript),
+            "java" => Some(Self::Java),
+            "cs" => Some(Self::CSharp),
+            "sh" | "bash" | "zsh" | "ksh" => Some(Self::Shell),
+            _ => None,
+        }
+    }
+
+    #[inline(always)]
+    fn line_comment(&self) -> &'static str {
+        match self {
+            Self::Python | Self::Shell => "#",
+            _ => "//",
+        }
+    }
+
+    #[inline(always)]
+    fn has_block_comments(&self) -> bool {
+        !matches!(self, Self::Python | Self::Shell)
+    }
+
+    #[inline(always)]
+    fn has_char_literals(&self) -> bool {
+        matches!(self, Self::Rust | Self::Go | Self::Java | Self::CSharp)
+    }
+
+    #[inline(always)]
+    fn has_triple_quoted_strings(&self) -> bool {
+        matches!(self, Self::Python | Self::Java)
+    }
+
+    #[inline(always)]
+    fn has_backtick_strings(&self) -> bool {
+        matches!(self, Self::Go | Self::JavaScript)
+    }
+
+    #[inline(always)]
+    fn has_regex_literals(&self) -> bool {
+        matches!(self, Self::JavaScript)
+    }
+
+    #[inline(always)]
+    fn is_ident_byte(&self, b: u8) -> bool {
+        b.is_ascii_alphanumeric() || b == b'_' || (b == b'$' && *self == Self::JavaScript)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Kind {
+    Ident(String),
+    Literal(String),
+    Assign,
+    Colon,
+    Dot,
+    Open,
+    Close,
+    Separator,
+    Other,
+}
+
+#[derive(Debug)]
+struct Token {
+    kind: Kind,
+    start: usize,
+}
+
+/// Lexer scans source code for string literals assigned to variables, fields and keys.
+/// Names the literals are assigned to are matched against key paths and keys with secrets of the patterns, member access is joined with a dot, like `self.password`.
+/// Literals in comments, in calls and in expressions are not matched, so words in docs do not produce findings.
+///
+#[derive(Debug)]
+pub struct Lexer {
+    patterns: Vec<Pattern>,
+}
+
+impl Lexer {
+    #[inline(always)]
+    pub fn new(patterns: Vec<Pattern>) -> Self {
+        Self { patterns }
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Tokenizes the source and returns literals with names they are assigned to as entries.
+    ///
+    #[inline(always)]
+    pub fn assignments(language: Language, s: &str, lines_ends: &impl LinesEndsProvider) -> Vec<Entry> {
+        let mut entries = Vec::new();
+        let mut name: Option<String> = None;
+        let mut pending: Option<String> = None;
+        let mut locked = false;
+        let mut declaring = false;
+        let mut at_start = true;
+        let mut previous = Kind::Separator;
+
+        for token in Tokenizer::new(language, s).tokenize() {
+            match &token.kind {
+                Kind::Ident(ident) if language == Language::Go && (ident == "var" || ident == "const") => declaring = true,
+                Kind::Ident(ident) => {
+                    if declaring {
+                        name = Some(ident.clone());
+                        locked = true;
+                        declaring = false;
+                    } else if !locked {
+                        name = match (&previous, name.take()) {
+                            (Kind::Dot, Some(n)) => Some(format!("{n}.{ident}")),
+                            _ => Some(ident.clone()),
+                        };
+                    }
+                    pending = None;
+                },
+                Kind::Literal(value) => {
+                    match pending.take() {
+                        Some(path) => entries.push(Entry { path, value: value.clone(), line: lines_ends.get_line(token.start).unwrap_or_default() }),
+                        None if !locked && at_start => name = Some(value.clone()),
+                        None => (),
+                    };
+                },
+                Kind::Assign => pending = name.clone(),
+                Kind::Colon => {
+                    pending = if locked { None } else { name.clone() };
+                    locked = name.is_some();
+                },
+                Kind::Dot | Kind::Other => pending = None,
+                Kind::Open | Kind::Separator => {
+                    name = None;
+                    pending = None;
+                    locked = false;
+                    declaring = false;
+                },
+                Kind::Close => {
+                    pending = None;
+                    locked = false;
+                    declaring = false;
+                },
+            };
+            at_start = matches!(token.kind, Kind::Open | Kind::Separator);
+            previous = token.kind;
+        }
+
+        entries
+    }
+}
+
+impl Scanner for Lexer {
+    #[inline(always)]
+    fn scan(&self, lines_ends: &impl LinesEndsProvider, s: &str, file: &str, branch: &str, sx: Sender<Option<Input>>) {
+        let Some(language) = Language::detect(file) else {
+            return;
+        };
+        let entries = Self::assignments(language, s, lines_ends);
+        if entries.is_empty() {
+            return;
+        }
+        for pattern in self.patterns.iter() {
+            pattern.scan_assignments(&entries, file, branch, sx.clone());
+        }
+    }
+}
+
+struct Tokenizer<'a> {
+    language: Language,
+    s: &'a str,
+    pos: usize,
+    tokens: Vec<Token>,
+}
+
+impl<'a> Tokenizer<'a> {
+    #[inline(always)]
+    fn new(language: Language, s: &'a str) -> Self {
+        Self { language, s, pos: 0, tokens: Vec::new() }
+    }
+
+    #[inline(always)]
+    fn rest(&self) -> &'a str {
+        &self.s[self.pos..]
+    }
+
+    #[inline(always)]
+    fn byte(&self, offset: usize) -> Option<u8> {
+        self.s.as_bytes().get(self.pos + offset).copied()
+    }
+
+    #[inline(always)]
+    fn push(&mut self, kind: Kind, start: usize) {
+        self.tokens.push(Token { kind, start });
+    }
+
+    fn tokenize(mut self) -> Vec<Token> {
+        while let Some(b) = self.byte(0) {
+            let start = self.pos;
+            let rest = self.rest();
+            match b {
+                b'\n' => {
+                    self.pos += 1;
+                    self.push(Kind::Separator, start);
+                },
+                _ if b.is_ascii_whitespace() => self.pos += 1,
+                _ if rest.starts_with(self.language.line_comment()) && self.is_comment_start() => {
+                    self.pos += rest.find('\n').unwrap_or(rest.len());
+                },
+                b'/' if self.language.has_block_comments() && rest.starts_with("/*") => {
+                    self.pos += rest[2..].find("*/").map(|e| e + 4).unwrap_or(rest.len());
+                },
+                b'/' if self.language.has_regex_literals() && self.is_regex_start() => self.regex(),
+                b'"' | b'\'' | b'`' => self.quoted(""),
+                b'@' | b'$' if self.language == Language::CSharp => {
+                    let prefix_len = rest.bytes().take_while(|b| *b == b'@' || *b == b'$').count();
+                    self.pos += prefix_len;
+                    match rest.as_bytes().get(prefix_len) {
+                        Some(b'"') => self.quoted(&rest[..prefix_len]),
+                        _ => self.push(Kind::Other, start),
+                    };
+                },
+                _ if b.is_ascii_digit() => {
+                    self.pos += rest.bytes().take_while(|b| b.is_ascii_alphanumeric() || *b == b'_' || *b == b'.').count();
+                    self.push(Kind::Other, start);
+                },
+                _ if self.language.is_ident_byte(b) => {
+                    let len = rest.bytes().take_while(|b| self.language.is_ident_byte(*b)).count();
+                    let ident = &rest[..len];
+                    match rest.as_bytes().get(len) {
+                        Some(b'"' | b'\'' | b'#') if self.is_string_prefix(ident, &rest[len..]) => {
+                            self.pos += len;
+                            self.quoted(ident);
+                        },
+                        _ => {
+                            self.pos += len;
+                            self.push(Kind::Ident(ident.to_string()), start);
+                        },
+                    };
+                },
+                b'=' => {
+                    match self.byte(1) {
+                        Some(b'=' | b'>' | b'~') => {
+                            self.pos += rest.bytes().take_while(|b| matches!(b, b'=' | b'>' | b'~')).count();
+                            self.push(Kind::Other, start);
+                        },
+                        _ => {
+                            self.pos += 1;
+                            self.push(Kind::Assign, start);
+                            self.bare_word();
+                        },
+                    };
+                },
+                b':' => match self.byte(1) {
+                    Some(b':') => {
+                        self.pos += 2;
+                        self.push(Kind::Other, start);
+                    },
+                    Some(b'=') => {
+                        self.pos += 2;
+                        self.push(Kind::Assign, start);
+                    },
+                    _ => {
+                        self.pos += 1;
+                        self.push(Kind::Colon, start);
+                    },
+                },
+                b'!' | b'<' | b'>' | b'+' | b'-' | b'*' | b'/' | b'%' | b'&' | b'|' | b'^' | b'?' | b'~' if self.byte(1) == Some(b'=') => {
+                    self.pos += 2;
+                    self.push(Kind::Other, start);
+                },
+                b'.' => {
+                    self.pos += 1;
+                    self.push(Kind::Dot, start);
+                },
+                b'(' | b'[' | b'{' => {
+                    self.pos += 1;
+                    self.push(Kind::Open, start);
+                },
+                b')' | b']' | b'}' => {
+                    self.pos += 1;
+                    self.push(Kind::Close, start);
+                },
+                b';' | b',' => {
+                    self.pos += 1;
+                    self.push(Kind::Separator, start);
+                },
+                _ => {
+                    self.pos += rest.chars().next().map(char::len_utf8).unwrap_or(1);
+                    self.push(Kind::Other, start);
+                },
+            };
+        }
+
+        self.tokens
+    }
+
+    /// Checks if the line comment marker starts the comment, shell comments start words only.
+    ///
+    #[inline(always)]
+    fn is_comment_start(&self) -> bool {
+        if self.language != Language::Shell || self.pos == 0 {
+            return true;
+        }
+        let previous = self.s.as_bytes()[self.pos - 1];
+        previous.is_ascii_whitespace() || previous == b';'
+    }
+
+    /// Checks if the slash starts the regex literal, that is when it starts the line or follows an operator, an opening bracket or a separator,
+    /// otherwise the slash is the division.
+    ///
+    #[inline(always)]
+    fn is_regex_start(&self) -> bool {
+        match self.s.as_bytes()[..self.pos].iter().rev().find(|b| !matches!(b, b' ' | b'\t' | b'\r')) {
+            None | Some(b'\n') => true,
+            Some(b) => b"(,=:[!&|?{;+-*%<>~^".contains(b),
+        }
+    }
+
+    /// Reads the regex literal with its flags starting at the current position, so quotes in the pattern do not open strings.
+    /// Slash not closed on the same line is taken for the division.
+    ///
+    fn regex(&mut self) {
+        let start = self.pos;
+        let rest = self.rest();
+        let bytes = rest.as_bytes();
+        let mut idx = 1;
+        let mut class = false;
+        let end = loop {
+            match bytes.get(idx) {
+                None | Some(b'\n') => break None,
+                Some(b'\\') => idx += 1,
+                Some(b'[') => class = true,
+                Some(b']') => class = false,
+                Some(b'/') if !class => break Some(idx + 1),
+                _ => (),
+            };
+            idx += 1;
+        };
+        self.pos += match end {
+            Some(end) => end + rest[end..].bytes().take_while(|b| b.is_ascii_alphabetic()).count(),
+            None => 1,
+        };
+        self.push(Kind::Other, start);
+    }
+
+    /// Checks if the identifier is the prefix of the string literal following it, like `r"..."` or `f'...'`.
+    ///
+    #[inline(always)]
+    fn is_string_prefix(&self, ident: &str, rest: &str) -> bool {
+        match self.language {
+            Language::Python => ident.len() <= 2 && ident.bytes().all(|b| b"rRbBuUfF".contains(&b)) && !rest.starts_with('#'),
+            Language::Rust => match ident {
+                "r" | "br" => rest.trim_start_matches('#').starts_with('"'),
+                "b" => rest.starts_with('"'),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Reads the quoted literal starting at the current position, the prefix tells if the literal is raw or verbatim.
+    ///
+    fn quoted(&mut self, prefix: &str) {
+        let start = self.pos - prefix.len();
+        let rest = self.rest();
+        let quote = &rest[..1];
+        let raw = prefix.contains(['r', 'R']);
+
+        if quote == "'" && self.language.has_char_literals() {
+            let from = if rest[1..].starts_with('\\') { 3 } else { 1 + rest[1..].chars().next().map(char::len_utf8).unwrap_or(0) };
+            let len = rest
+                .get(from..)
+                .and_then(|body| body.char_indices().take(MAX_CHAR_LITERAL_LEN).find(|(_, c)| *c == '\'' || *c == '\n'))
+                .filter(|(_, c)| *c == '\'')
+                .map(|(idx, _)| from + idx + 1)
+                .unwrap_or(1);
+            self.pos += len;
+            self.push(Kind::Other, start);
+            return;
+        }
+
+        let literal = if self.language == Language::Rust && raw {
+            let hashes = rest.bytes().take_while(|b| *b == b'#').count();
+            let closing = format!("\"{}", "#".repeat(hashes));
+            let body = &rest[hashes + 1..];
+            body.find(&closing).map(|end| (body[..end].to_string(), hashes + 1 + end + closing.len()))
+        } else if self.language.has_triple_quoted_strings() && (rest.starts_with("\"\"\"") || rest.starts_with("'''")) {
+            let delimiter = &rest[..3];
+            Self::closing(&rest[3..], delimiter, !raw).map(|end| (Self::unescape(&rest[3..3 + end], raw), 3 + end + 3))
+        } else if quote == "`" && !self.language.has_backtick_strings() {
+            None
+        } else if prefix.contains('@') {
+            Self::verbatim_end(&rest[1..]).map(|end| (rest[1..1 + end].replace("\"\"", "\""), 1 + end + 1))
+        } else {
+            let escapes = !matches!((quote, self.language), ("'", Language::Shell) | ("`", Language::Go));
+            Self::closing(&rest[1..], quote, escapes).map(|end| (Self::unescape(&rest[1..1 + end], raw || !escapes), 1 + end + 1))
+        };
+
+        match literal {
+            Some((value, len)) => {
+                self.pos += len;
+                self.push(Kind::Literal(value), start);
+            },
+            None => {
+                self.pos += 1;
+                self.push(Kind::Other, start);
+            },
+        };
+    }
+
+    /// Reads the unquoted shell word following the assignment, like `TOKEN=value`.
+    ///
+    #[inline(always)]
+    fn bare_word(&mut self) {
+        if self.language != Language::Shell {
+            return;
+        }
+        let rest = self.rest();
+        let len = rest.bytes().take_while(|b| !b.is_ascii_whitespace() && !matches!(b, b';' | b'"' | b'\'' | b'`' | b'(' | b')' | b'|' | b'&')).count();
+        if len == 0 || rest.starts_with('$') {
+            return;
+        }
+        let start = self.pos;
+        self.pos += len;
+        self.push(Kind::Literal(rest[..len].to_string()), start);
+    }
+
+    /// Returns the index of the closing delimiter, escaped delimiters are skipped if escapes are allowed.
+    ///
+    #[inline(always)]
+    fn closing(body: &str, delimiter: &str, escapes: bool) -> Option<usize> {
+        let mut idx = 0;
+        while idx < body.len() {
+            let rest = &body[idx..];
+            if escapes && rest.starts_with('\\') {
+                idx += 1 + rest[1..].chars().next().map(char::len_utf8).unwrap_or(0);
+                continue;
+            }
+            if rest.starts_with(delimiter) {
+                return Some(idx);
+            }
+            idx += rest.chars().next().map(char::len_utf8).unwrap_or(1);
+        }
+        None
+    }
+
+    /// Returns the index of the quote closing C# verbatim string, doubled quotes are escaped quotes.
+    ///
+    #[inline(always)]
+    fn verbatim_end(body: &str) -> Option<usize> {
+        let bytes = body.as_bytes();
+        let mut idx = 0;
+        while idx < bytes.len() {
+            if bytes[idx] == b'"' {
+                if bytes.get(idx + 1) == Some(&b'"') {
+                    idx += 2;
+                    continue;
+                }
+                return Some(idx);
+            }
+            idx += 1;
+        }
+        None
+    }
+
+    #[inline(always)]
+    fn unescape(value: &str, raw: bool) -> String {
+        if raw || !value.contains('\\') {
+            return value.to_string();
+        }
+        let mut result = String::with_capacity(value.len());
+        let mut chars = value.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                result.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('n') => result.push('\n'),
+                Some('t') => result.push('\t'),
+                Some('r') => result.push('\r'),
+                Some('0') => result.push('\0'),
+                Some(c @ ('\\' | '"' | '\'' | '`')) => result.push(c),
+                Some(other) => {
+                    result.push('\\');
+                    result.push(other);
+                },
+                None => result.push('\\'),
+            };
+        }
+
+        result
+    }
+}
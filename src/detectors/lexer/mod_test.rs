@@ -0,0 +1,126 @@
+use crate::detectors::lexer::{Language, Lexer};
+use crate::detectors::regex::Builder;
+use crate::detectors::Scanner;
+use crate::lines::LinesEnds;
+
+mod tests {
+    use crossbeam_channel::unbounded;
+
+    use crate::reporter::Input;
+
+    use super::*;
+
+    fn assignments(language: Language, s: &str) -> Vec<(String, String, usize)> {
+        Lexer::assignments(language, s, &LinesEnds::from_str(s)).into_iter().map(|e| (e.path, e.value, e.line)).collect()
+    }
+
+    fn entry(path: &str, value: &str, line: usize) -> (String, String, usize) {
+        (path.to_string(), value.to_string(), line)
+    }
+
+    #[test]
+    fn it_should_detect_language_by_file_extension() {
+        assert_eq!(Language::detect("src/main.rs"), Some(Language::Rust));
+        assert_eq!(Language::detect("cmd/server.go"), Some(Language::Go));
+        assert_eq!(Language::detect("app/settings.py"), Some(Language::Python));
+        assert_eq!(Language::detect("web/index.TSX"), Some(Language::JavaScript));
+        assert_eq!(Language::detect("Main.java"), Some(Language::Java));
+        assert_eq!(Language::detect("Program.cs"), Some(Language::CSharp));
+        assert_eq!(Language::detect("deploy.sh"), Some(Language::Shell));
+        assert_eq!(Language::detect("config.yaml"), None);
+        assert_eq!(Language::detect("Makefile"), None);
+    }
+
+    #[test]
+    fn it_should_find_rust_assignments() {
+        let s = "// password = \"in comment\"\nconst API_KEY: &str = \"k1\";\nlet mut token = r#\"t\"1\"#;\nlet c = 'x'; fn f<'a>(s: &'a str) {}\nlet cfg = Config { password: \"p1\".to_string() };\nif secret == \"no\" { call(\"no\"); }\n";
+
+        assert_eq!(
+            assignments(Language::Rust, s),
+            vec![entry("API_KEY", "k1", 2), entry("token", "t\"1", 3), entry("password", "p1", 5)]
+        );
+    }
+
+    #[test]
+    fn it_should_find_go_assignments() {
+        let s = "var dbPassword string = \"p1\"\ntoken := `raw\\n`\ncfg := Config{Secret: \"s1\"}\n/* key = \"in comment\" */\n";
+
+        assert_eq!(
+            assignments(Language::Go, s),
+            vec![entry("dbPassword", "p1", 1), entry("token", "raw\\n", 2), entry("Secret", "s1", 3)]
+        );
+    }
+
+    #[test]
+    fn it_should_find_python_assignments() {
+        let s = "\"\"\"Set password = 'docs' here.\"\"\"\nself.api_key = f'k\\'1'\npassword: str = \"\"\"p\n1\"\"\"\nconf = {\"token\": r\"t\\1\", 'url': os.environ['URL']}\nconnect(user=\"u1\")  # secret = 'comment'\n";
+
+        assert_eq!(
+            assignments(Language::Python, s),
+            vec![
+                entry("self.api_key", "k'1", 2),
+                entry("password", "p\n1", 3),
+                entry("token", "t\\1", 5),
+                entry("user", "u1", 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_find_javascript_java_and_csharp_assignments() {
+        assert_eq!(
+            assignments(Language::JavaScript, "const client = { apiKey: 'k1', url: `https://${host}` };\nprocess.env.TOKEN = \"t1\";\n"),
+            vec![entry("apiKey", "k1", 1), entry("url", "https://${host}", 1), entry("process.env.TOKEN", "t1", 2)]
+        );
+        assert_eq!(
+            assignments(Language::Java, "private static final String PASSWORD = \"p1\";\nchar c = '\\'';\n"),
+            vec![entry("PASSWORD", "p1", 1)]
+        );
+        assert_eq!(
+            assignments(Language::CSharp, "const string ConnectionString = @\"Server=.;Password=\"\"p1\"\"\";\nvar token = $\"t{id}\";\n"),
+            vec![entry("ConnectionString", "Server=.;Password=\"p1\"", 1), entry("token", "t{id}", 2)]
+        );
+    }
+
+    #[test]
+    fn it_should_skip_javascript_regex_literals() {
+        let s = "const re = /\"/; api_password = \"p1\";\npassword = \"p2\";\nconst half = total / 2; const quote = /['\"]/g.test(s);\ntoken = \"t1\";\n";
+
+        assert_eq!(
+            assignments(Language::JavaScript, s),
+            vec![entry("api_password", "p1", 1), entry("password", "p2", 2), entry("token", "t1", 4)]
+        );
+    }
+
+    #[test]
+    fn it_should_find_shell_assignments() {
+        let s = "#!/bin/sh\nexport TOKEN=abc123 # comment\nPASSWORD='p$1'\ncurl --header=\"x\" -u \"$USER\" ${#list}\nKEY=$OTHER\n";
+
+        assert_eq!(
+            assignments(Language::Shell, s),
+            vec![entry("TOKEN", "abc123", 2), entry("PASSWORD", "p$1", 3), entry("header", "x", 4)]
+        );
+    }
+
+    #[test]
+    fn it_should_scan_literals_assigned_to_matching_names() {
+        let pattern = Builder::new()
+            .with_name("Password")
+            .with_key_paths(&[r#"(?i)password$"#], &[r#"^\S{8,}$"#])
+            .try_build_scanner()
+            .expect("password pattern");
+        let lexer = Lexer::new(vec![pattern]);
+        let s = "// the password is \"not-a-secret\"\nlet db_password = \"Zx9!kLm2Qw\";\nlog(\"password\", \"Zx9!kLm2Qw\");\n";
+        let (sx, rx) = unbounded();
+        lexer.scan(&LinesEnds::from_str(s), s, "src/db.rs", "---- test", sx);
+
+        let mut found = Vec::new();
+        for result in rx.iter() {
+            let Some(Input::Finding(f)) = result else {
+                panic!("expected finding, got {:?}", result);
+            };
+            found.push((f.raw_result, f.line));
+        }
+        assert_eq!(found, vec![("db_password: Zx9!kLm2Qw".to_string(), 2)]);
+    }
+}
@@ -1,6 +1,8 @@
 pub mod errors;
 pub mod regex;
+pub mod lexer;
 pub mod fingerprint;
+pub mod entropy;
 
 use std::fmt::Debug;
 use crate::lines::LinesEndsProvider;
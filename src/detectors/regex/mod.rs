@@ -8,10 +8,12 @@ use serde_yaml::to_string as yaml_to_string;
 use std::fs::{read_to_string, write};
 use std::path::Path;
 use crate::repository::dtos::RegexConfigurationDataDto;
-use crate::result::{DecoderType, DetectorType, Secret};
+use crate::result::{DecoderType, DetectorType, Rule, Secret, Severity};
 use crate::lines::LinesEndsProvider;
 use crate::reporter::Input;
+use crate::structured::Entry;
 use super::Scanner;
+use super::entropy::{shannon, score, Charset};
 use super::errors::DetectorError;
 use std::convert::Into;
 use crate::repository::dtos::{
@@ -43,8 +45,24 @@ pub struct Schema {
     secret_regexes: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     keys_with_secrets: Option<Vec<KeysWithSecrets>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    key_paths: Option<Vec<KeysWithSecrets>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    lexer: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     keys_required: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_entropy: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    charset: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    severity: Option<Severity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remediation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    references: Option<Vec<String>>,
 }
 
 impl Schema {
@@ -69,6 +87,20 @@ impl Schema {
         Ok(())
     }
 
+    /// Describes the schema as the rule of reported findings.
+    ///
+    #[inline(always)]
+    pub fn rule(&self) -> Rule {
+        Rule {
+            id: self.name.clone(),
+            description: self.description.clone(),
+            groups: self.groups.clone().unwrap_or_default(),
+            severity: self.severity.unwrap_or_default(),
+            tags: self.tags.clone().unwrap_or_default(),
+            remediation: self.remediation.clone(),
+            references: self.references.clone().unwrap_or_default(),
+        }
+    }
 }
 
 impl TryInto<Group> for String {
@@ -132,6 +164,10 @@ impl Into<RegexConfigurationCreateDto> for Schema {
             keys_with_secrets = Some(some_keys_with_secrets);
         }
 
+        let key_paths = self.key_paths.map(|kps| {
+            kps.into_iter().map(|k| KeysWithSecretsDto { keys: k.keys, secrets: k.secrets }).collect()
+        });
+
         RegexConfigurationCreateDto {
             name: self.name,
             description: self.description.unwrap_or_default(),
@@ -139,7 +175,15 @@ impl Into<RegexConfigurationCreateDto> for Schema {
             config: ConfigDto {
                 secret_regexes: self.secret_regexes,
                 keys_with_secrets,
+                key_paths,
+                lexer: self.lexer,
                 keys_required: self.keys_required,
+                min_entropy: self.min_entropy,
+                charset: self.charset,
+                severity: self.severity,
+                tags: self.tags,
+                remediation: self.remediation,
+                references: self.references,
             },
             contributor_pem_hash: [0;64],
             signature: [0; 512],
@@ -175,13 +219,25 @@ impl From<RegexConfigurationDataDto> for Schema {
             }
         }
 
+        let key_paths = dto.config.key_paths.filter(|kps| !kps.is_empty()).map(|kps| {
+            kps.into_iter().map(|k| KeysWithSecrets { keys: k.keys, secrets: k.secrets }).collect()
+        });
+
         Schema {
             name: dto.name,
             description: Some(dto.description),
             groups: groups,
             keys_with_secrets,
+            key_paths,
+            lexer: dto.config.lexer,
             secret_regexes: dto.config.secret_regexes,
             keys_required: dto.config.keys_required,
+            min_entropy: dto.config.min_entropy,
+            charset: dto.config.charset,
+            severity: dto.config.severity,
+            tags: dto.config.tags,
+            remediation: dto.config.remediation,
+            references: dto.config.references,
         }
     }
 }
@@ -192,12 +248,122 @@ struct Variables {
     reg: Vec<Regex>,
 }
 
+/// KeyPaths matches entries of structured files, keys are regexes over the key path and secrets are regexes over the value.
+///
+#[derive(Debug)]
+struct KeyPaths {
+    keys: Vec<Regex>,
+    secrets: Vec<Regex>,
+}
+
 #[derive(Debug)]
 pub struct Pattern {
     name: String,
     secret_regex: Vec<Regex>,
     variables: Vec<Variables>,
+    key_paths: Vec<KeyPaths>,
+    lexer: bool,
     keys_required: Vec<String>,
+    min_entropy: Option<f64>,
+    charset: Option<Charset>,
+    severity: Severity,
+    tags: Vec<String>,
+    remediation: Option<String>,
+    references: Vec<String>,
+}
+
+impl Pattern {
+    /// Checks if the value satisfies the charset and the minimum entropy of the pattern.
+    ///
+    #[inline(always)]
+    fn accepts(&self, value: &str) -> bool {
+        if let Some(charset) = &self.charset {
+            if !charset.contains(value) {
+                return false;
+            }
+        }
+        if let Some(min_entropy) = self.min_entropy {
+            if shannon(value) < min_entropy {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Checks if the pattern matches entries of structured files.
+    ///
+    #[inline(always)]
+    pub fn has_key_paths(&self) -> bool {
+        !self.key_paths.is_empty()
+    }
+
+    /// Checks if the pattern is matched by the lexer in source files instead of the regexes over the whole source.
+    ///
+    #[inline(always)]
+    pub fn uses_lexer(&self) -> bool {
+        self.lexer
+    }
+
+    /// Scans entries of the structured file, the key path of the entry shall match the key regex and the value the secret regex.
+    /// Each entry is reported at most once with the line the value starts in.
+    ///
+    #[inline(always)]
+    pub fn scan_entries(&self, entries: &[Entry], file: &str, branch: &str, sx: Sender<Option<Input>>) {
+        self.scan_matching_entries(entries, false, file, branch, sx);
+    }
+
+    /// Scans string literals of the source with names they are assigned to, the name shall match the key regex of key paths
+    /// or contain the key of keys with secrets, and the literal the secret regex.
+    ///
+    #[inline(always)]
+    pub fn scan_assignments(&self, assignments: &[Entry], file: &str, branch: &str, sx: Sender<Option<Input>>) {
+        self.scan_matching_entries(assignments, true, file, branch, sx);
+    }
+
+    #[inline(always)]
+    fn scan_matching_entries(&self, entries: &[Entry], with_variables: bool, file: &str, branch: &str, sx: Sender<Option<Input>>) {
+        for entry in entries.iter() {
+            let key_paths = self.key_paths.iter()
+                .filter(|kp| kp.keys.iter().any(|k| k.is_match(&entry.path)))
+                .flat_map(|kp| kp.secrets.iter());
+            let variables = self.variables.iter()
+                .filter(|v| with_variables && v.aho.is_match(&entry.path))
+                .flat_map(|v| v.reg.iter());
+            let Some(secret) = key_paths.chain(variables)
+                .filter_map(|r| r.find(&entry.value))
+                .map(|m| m.as_str())
+                .find(|s| self.accepts(s)) else {
+                continue;
+            };
+            let raw_result = format!("{}: {}", entry.path, secret);
+            let score = (score(secret, self.charset.as_ref()) * 100.0).round() / 100.0;
+            let _ = sx.send(Some(Input::Finding(Box::new(self.secret(raw_result, score, file, branch, entry.line)))));
+        }
+    }
+
+    #[inline(always)]
+    fn secret(&self, raw_result: String, score: f64, file: &str, branch: &str, line: usize) -> Secret {
+        Secret {
+            detector_type: DetectorType::Unique(self.name.clone()),
+            decoder_type: DecoderType::Plane,
+            raw_result,
+            score,
+            severity: self.severity,
+            tags: self.tags.clone(),
+            remediation: self.remediation.clone(),
+            references: self.references.clone(),
+            branch: branch.to_string(),
+            file: file.to_string(),
+            line,
+            author: None,
+            email: None,
+            commit: None,
+            commit_time: None,
+            metadata: None,
+            fingerprint: None,
+            context: None,
+        }
+    }
 }
 
 impl Scanner for Pattern {
@@ -266,6 +432,9 @@ where
                     let Some(secret) = secrets.get(0) else {
                         continue 'regs_loop;
                     };
+                    if !self.scanner.accepts(secret.as_str()) {
+                        continue 'regs_loop;
+                    }
                     let position = SecretPosition::new(key.end()+secret.start(), key.end()+secret.end());
                     if self.unique.contains_key(&position) {
                         continue 'regs_loop;
@@ -282,6 +451,9 @@ where
             let Some(secret) = secrets.get(0) else {
               continue 'regex_loop;
             };
+            if !self.scanner.accepts(secret.as_str()) {
+                continue 'regex_loop;
+            }
             let position = SecretPosition::new(secret.start(), secret.end());
             if self.unique.contains_key(&position) {
                 continue 'regex_loop;
@@ -331,19 +503,17 @@ where
             if found_count != 0 {
                 continue 'positions_loop;
             }
-            let secret = Secret {
-                detector_type: DetectorType::Unique(self.scanner.name.clone()),
-                decoder_type: DecoderType::Plane,
-                raw_result: Self::stringify(&raw),
-                branch: self.branch.to_string(),
-                file: self.file.to_string(),
-                line: self.line_ends.get_line(start.unwrap_or_default()).unwrap_or_default(),
-                author: None,
-            };
+            let secret = self.scanner.secret(
+                Self::stringify(&raw),
+                self.score(&raw),
+                self.file,
+                self.branch,
+                self.line_ends.get_line(start.unwrap_or_default()).unwrap_or_default(),
+            );
 
             start = Some(position.start);
 
-            let _ = self.sx.send(Some(Input::Finding(secret)));
+            let _ = self.sx.send(Some(Input::Finding(Box::new(secret))));
 
             keys.clear();
             keys.insert(&item.key);
@@ -365,19 +535,27 @@ where
                 return;
             }
 
-            let secret = Secret {
-                detector_type: DetectorType::Unique(self.scanner.name.clone()),
-                decoder_type: DecoderType::Plane,
-                raw_result: Self::stringify(&raw),
-                branch: self.branch.to_string(),
-                file: self.file.to_string(),
-                line: self.line_ends.get_line(start.unwrap_or_default()).unwrap_or_default(),
-                author: None,
-            };
-            let _ = self.sx.send(Some(Input::Finding(secret)));
+            let secret = self.scanner.secret(
+                Self::stringify(&raw),
+                self.score(&raw),
+                self.file,
+                self.branch,
+                self.line_ends.get_line(start.unwrap_or_default()).unwrap_or_default(),
+            );
+            let _ = self.sx.send(Some(Input::Finding(Box::new(secret))));
         }
     }
 
+    #[inline(always)]
+    fn score(&self, raw: &[&SecretItem]) -> f64 {
+        if raw.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = raw.iter().map(|item| score(item.value, self.scanner.charset.as_ref())).sum();
+
+        (total / raw.len() as f64 * 100.0).round() / 100.0
+    }
+
     #[inline(always)]
     fn stringify(raw: &[&SecretItem]) -> String {
         let mut result: String = String::new();
@@ -398,7 +576,15 @@ pub struct Builder {
     name: Option<String>,
     secret_regexes: Vec<String>,
     variables: Vec<(Vec<String>, Vec<String>)>,
+    key_paths: Vec<(Vec<String>, Vec<String>)>,
+    lexer: bool,
     keys_required: Vec<String>,
+    min_entropy: Option<f64>,
+    charset: Option<String>,
+    severity: Severity,
+    tags: Vec<String>,
+    remediation: Option<String>,
+    references: Vec<String>,
 }
 
 impl Builder {
@@ -408,7 +594,15 @@ impl Builder {
             name: None,
             secret_regexes: Vec::new(),
             variables: Vec::new(),
+            key_paths: Vec::new(),
+            lexer: false,
             keys_required: Vec::new(),
+            min_entropy: None,
+            charset: None,
+            severity: Severity::default(),
+            tags: Vec::new(),
+            remediation: None,
+            references: Vec::new(),
         }
     }
 
@@ -447,6 +641,27 @@ impl Builder {
         self
     }
 
+    /// Populates key paths with given key regexes and secret regexes.
+    /// Key regexes match the path of keys in structured files, like `database.password`, secret regexes match the value.
+    ///
+    #[inline(always)]
+    pub fn with_key_paths(&mut self, keys: &[&str], secrets: &[&str]) -> &mut Self {
+        self.key_paths.push((
+            keys.iter().map(|k| k.to_string()).collect(),
+            secrets.iter().map(|s| s.to_string()).collect(),
+        ));
+        self
+    }
+
+    /// Makes the lexer match the pattern in source files, key paths and keys with secrets are matched against names
+    /// string literals are assigned to, and the secret regexes aren't matched over the source.
+    ///
+    #[inline(always)]
+    pub fn with_lexer(&mut self) -> &mut Self {
+        self.lexer = true;
+        self
+    }
+
     /// Populates keys required to filter true positive secrets.
     ///
     #[inline(always)]
@@ -457,6 +672,58 @@ impl Builder {
         self
     }
 
+    /// Sets minimum Shannon entropy in bits per character the secret value must have.
+    ///
+    #[inline(always)]
+    pub fn with_min_entropy(&mut self, min_entropy: f64) -> &mut Self {
+        self.min_entropy = Some(min_entropy);
+        self
+    }
+
+    /// Sets charset the secret value must consist of: hex, numeric, alphanumeric, base64 or base64url.
+    ///
+    #[inline(always)]
+    pub fn with_charset(&mut self, charset: &str) -> &mut Self {
+        self.charset = Some(charset.to_string());
+        self
+    }
+
+    /// Sets severity of the findings, medium if not set.
+    ///
+    #[inline(always)]
+    pub fn with_severity(&mut self, severity: Severity) -> &mut Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Populates free-form tags of the findings.
+    ///
+    #[inline(always)]
+    pub fn with_tags(&mut self, tags: &[&str]) -> &mut Self {
+        for tag in tags.iter() {
+            self.tags.push(tag.to_string());
+        }
+        self
+    }
+
+    /// Sets remediation telling what to do with the found secret.
+    ///
+    #[inline(always)]
+    pub fn with_remediation(&mut self, remediation: &str) -> &mut Self {
+        self.remediation = Some(remediation.to_string());
+        self
+    }
+
+    /// Populates reference URLs with more information about the secret.
+    ///
+    #[inline(always)]
+    pub fn with_references(&mut self, references: &[&str]) -> &mut Self {
+        for reference in references.iter() {
+            self.references.push(reference.to_string());
+        }
+        self
+    }
+
     /// Tries to build a scanner.
     ///
     #[inline(always)]
@@ -477,6 +744,16 @@ impl Builder {
             }
         }
 
+        let mut key_paths = Vec::new();
+        for (keys, secrets) in self.key_paths.iter() {
+            if !keys.is_empty() && !secrets.is_empty() {
+                key_paths.push(KeyPaths {
+                    keys: keys.iter().map(|k| RegexBuilder::new(k).build()).collect::<Result<_, _>>()?,
+                    secrets: secrets.iter().map(|s| RegexBuilder::new(s).build()).collect::<Result<_, _>>()?,
+                });
+            }
+        }
+
         let mut secret_regex = Vec::new();
 
         for rgx in self.secret_regexes.iter() {
@@ -491,7 +768,18 @@ impl Builder {
             },
             secret_regex,
             variables: variables_schema,
+            key_paths,
+            lexer: self.lexer,
             keys_required: self.keys_required.to_owned(),
+            min_entropy: self.min_entropy,
+            charset: match &self.charset {
+                Some(charset) => Some(charset.as_str().try_into()?),
+                None => None,
+            },
+            severity: self.severity,
+            tags: self.tags.to_owned(),
+            remediation: self.remediation.to_owned(),
+            references: self.references.to_owned(),
         })
    }
 }
@@ -537,11 +825,41 @@ impl TryFrom<&Schema> for Pattern {
 
         let mut builder = Builder::new();
         builder.with_name(&s.name);
+        for kps in s.key_paths.iter().flatten() {
+            let (Some(keys), Some(secrets)) = (&kps.keys, &kps.secrets) else {
+                continue;
+            };
+            builder.with_key_paths(
+                &keys.iter().map(|k| k.as_str()).collect::<Vec<&str>>(),
+                &secrets.iter().map(|s| s.as_str()).collect::<Vec<&str>>(),
+            );
+        }
+        if s.lexer.unwrap_or_default() {
+            builder.with_lexer();
+        }
         builder.with_secret_regexes(&secrets);
         builder.with_keys_required(&keys_required);
         for kws in keys_w_secrets.iter() {
             builder.with_variables(&kws.0, &kws.1);
         }
+        if let Some(min_entropy) = s.min_entropy {
+            builder.with_min_entropy(min_entropy);
+        }
+        if let Some(severity) = s.severity {
+            builder.with_severity(severity);
+        }
+        if let Some(tags) = &s.tags {
+            builder.with_tags(&tags.iter().map(|t| t.as_str()).collect::<Vec<&str>>());
+        }
+        if let Some(remediation) = &s.remediation {
+            builder.with_remediation(remediation);
+        }
+        if let Some(references) = &s.references {
+            builder.with_references(&references.iter().map(|r| r.as_str()).collect::<Vec<&str>>());
+        }
+        if let Some(charset) = &s.charset {
+            builder.with_charset(charset);
+        }
 
         let Ok(scanner) = builder.try_build_scanner() else {
             return Err(DetectorError::BuildScannerFailure("cannot build scanner from schema".to_string()));
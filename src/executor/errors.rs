@@ -1,6 +1,9 @@
 use thiserror::Error;
 use crate::source::errors::SourceError;
 use crate::inspect::errors::InspectorError;
+use crate::allowlist::errors::AllowlistError;
+use crate::baseline::errors::BaselineError;
+use crate::filter::errors::FilterError;
 use std::io;
 use zip::result::ZipError;
 
@@ -10,6 +13,12 @@ use zip::result::ZipError;
 pub enum ExecutorError {
     #[error("failed due to inspector failuer, {0}")]
     InspectorFailure(#[from] InspectorError),
+    #[error("failed due to allowlist failuer, {0}")]
+    AllowlistFailure(#[from] AllowlistError),
+    #[error("failed due to baseline failuer, {0}")]
+    BaselineFailure(#[from] BaselineError),
+    #[error("failed due to path filter failuer, {0}")]
+    FilterFailure(#[from] FilterError),
     #[error("failed due to source io failuer, {0}")]
     GitSourceIoFailure(#[from] SourceError),
     #[error("failed due to parameter is lacking, {0}")]
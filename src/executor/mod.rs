@@ -2,27 +2,38 @@ pub mod errors;
 
 use std::{collections::HashSet, path::PathBuf, sync::Arc};
 use crossbeam_channel::{unbounded, Sender, Receiver};
-use std::{fs::{read, read_to_string, File},  io::prelude::*};
+use std::fs::read;
 use rayon::iter::ParallelBridge;
 use rayon::prelude::ParallelIterator;
 use std::thread::spawn;
 use errors::ExecutorError;
 use crate::{
-    inspect::Inspector,
+    allowlist::Allowlist,
+    archive::{Extracted, Extractor, Limits},
+    baseline::Baseline,
+    binary::text,
+    detectors::fingerprint::{Lab, SecretSuspectsData},
+    filter::{PathFilter, Preset},
+    inspect::{errors::InspectorError, Inspector, Origin},
     reporter::Input,
+    result::{Skip, SkipReason},
     source::{
-        BranchLevel, DataSource, DirectoryProvider, RepositoryProvider, Source,
+        BranchLevel, DataSource, DirectoryProvider, RepositoryProvider, Source, git::{Blob, Chunk, CommitRange},
     },
 };
-use zip::ZipArchive;
-use tar::Archive;
 
-const GUESS_OMIT_SIZE: usize = 64;
+#[cfg(test)]
+mod mod_test;
+
 const FILE_SYSTEM: &str = "------ FILE SYSTEM ------";
+const GIT_HISTORY: &str = "------ GIT HISTORY ------";
+const GIT_STAGED: &str = "------ GIT STAGED ------";
+const GIT_HEAD: &str = "HEAD";
 
 struct DataWithInfo {
     data: String,
     file_name: String,
+    origin: Origin,
 }
 
 /// Config contains full configuration of Executor to run.
@@ -33,26 +44,45 @@ pub struct Config<'a> {
     pub path: Option<&'a PathBuf>,
     pub url: Option<&'a String>,
     pub config: Option<&'a PathBuf>,
+    pub fingerprints: Option<&'a PathBuf>,
+    pub allowlist: Option<&'a PathBuf>,
+    pub baseline: Option<&'a PathBuf>,
+    pub write_baseline: Option<&'a PathBuf>,
+    pub include: Option<&'a String>,
     pub omit: Option<&'a String>,
-    pub nodeps: Option<String>,
+    pub nodeps: bool,
+    pub ignore_files: bool,
     pub branch_level: BranchLevel,
     pub branches: &'a Option<Vec<String>>,
     pub sx_input: Sender<Option<Input>>,
     pub decompress: bool,
+    pub limits: Limits,
     pub scan_binary: bool,
+    pub history: bool,
+    pub range: CommitRange,
+    pub diff_base: Option<&'a String>,
+    pub diff_head: Option<&'a String>,
+    pub staged: bool,
+    pub context_lines: usize,
 }
 
 /// Executes the scanners with given setup.
 ///
 pub struct Executor {
     source: Source,
-    omit: Vec<String>,
+    filter: PathFilter,
     inspector: Arc<Inspector>,
     branch_level: BranchLevel,
     branches: Option<HashSet<String>>,
     sx_input: Sender<Option<Input>>,
-    decompress: bool,
+    extractor: Option<Extractor>,
+    limits: Limits,
     scan_binary: bool,
+    history: bool,
+    range: CommitRange,
+    diff: Option<(String, String)>,
+    staged: bool,
+    write_baseline: Option<PathBuf>,
 }
 
 impl Executor {
@@ -73,37 +103,88 @@ impl Executor {
             },
         }?;
 
-        let mut omit_patterns: Vec<String> = Vec::with_capacity(GUESS_OMIT_SIZE);
-        if let Some(patterns) = &cfg.omit {
+        let mut filter = PathFilter::new(cfg.ignore_files);
+        if let Some(patterns) = &cfg.include {
             for pattern in patterns.split(" ") {
-                omit_patterns.push(pattern.to_string())
+                filter.with_include(pattern)?;
             }
         }
-        if let Some(patterns) = &cfg.nodeps {
+        if let Some(patterns) = &cfg.omit {
             for pattern in patterns.split(" ") {
-                omit_patterns.push(pattern.to_string())
+                filter.with_exclude(pattern)?;
             }
         }
+        if cfg.nodeps {
+            filter.with_preset(Preset::Dependencies)?;
+        }
 
-        let inspector = Arc::new(Inspector::try_new(
+        let history = cfg.history || !cfg.range.is_unbounded();
+
+        let mut inspector = Inspector::try_new(
             config_path.to_str().unwrap_or_default(),
             cfg.sx_input.clone(),
-        )?);
+        )?;
+        if let Some(fingerprints) = cfg.fingerprints {
+            let suspects = SecretSuspectsData::read_from_file(fingerprints).map_err(InspectorError::from)?;
+            inspector.with_fingerprints(Lab::from_suspects(&suspects).map_err(InspectorError::from)?);
+        }
+        if let Some(allowlist) = cfg.allowlist {
+            inspector.with_allowlist(Allowlist::read_from_yaml_file(allowlist)?);
+        }
+        if let Some(baseline) = cfg.baseline {
+            inspector.with_baseline(Baseline::read_from_json_file(baseline)?);
+        }
+        if cfg.write_baseline.is_some() {
+            inspector.with_recorder();
+        }
+        inspector.with_context(cfg.context_lines);
+        if let Some(root) = source.path_buf() {
+            inspector.with_root(&root);
+        }
+        if cfg.data_source == DataSource::Git && !history && !cfg.staged {
+            if let Ok(blamer) = source.blamer() {
+                inspector.with_attributor(Box::new(blamer));
+            }
+        }
+        let inspector = Arc::new(inspector);
 
         Ok(Self {
             source,
-            omit: omit_patterns,
+            filter,
             inspector,
             branch_level: cfg.branch_level,
             branches: if let Some(branches) = cfg.branches { Some(branches.into_iter().map(|v| v.to_owned()).collect::<HashSet<String>>()) } else { None },
             sx_input: cfg.sx_input.clone(),
-            decompress: cfg.decompress,
+            extractor: if cfg.decompress { Some(Extractor::new(cfg.limits)) } else { None },
+            limits: cfg.limits,
             scan_binary: cfg.scan_binary,
+            history,
+            range: cfg.range.clone(),
+            diff: cfg.diff_base.map(|base| (
+                base.to_string(),
+                cfg.diff_head.map(|head| head.to_string()).unwrap_or(GIT_HEAD.to_string()),
+            )),
+            staged: cfg.staged,
+            write_baseline: cfg.write_baseline.cloned(),
         })
     }
 
+    /// Executes the scan and writes the baseline of the findings if requested.
+    ///
     #[inline(always)]
     pub fn execute(&mut self) -> Result<(), ExecutorError>{
+        self.scan()?;
+        if let Some(path) = &self.write_baseline {
+            if let Some(mut baseline) = self.inspector.recorded_baseline() {
+                baseline.write_to_json_file(path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn scan(&mut self) -> Result<(), ExecutorError>{
         let mut branches_to_scan = Vec::new();
         match &self.branch_level {
            BranchLevel::Head => branches_to_scan.push(FILE_SYSTEM.to_string()),
@@ -119,11 +200,56 @@ impl Executor {
            },
         };
 
+        let (sx_error, rx_error): (Sender<ExecutorError>, Receiver<ExecutorError>) = unbounded();
+
+        if self.staged {
+            let (sx_data, rx_data): (Sender<Option<DataWithInfo>>, Receiver<Option<DataWithInfo>>) = unbounded();
+            let result = self.walk_staged(sx_data, sx_error);
+            if result.is_ok() {
+                self.process(rx_data, GIT_STAGED);
+            }
+            let _ = self.sx_input.send(None);
+            let _ = self.source.flush();
+
+            return result.and_then(|_| walk_error(&rx_error));
+        }
+
+        if let Some((base, head)) = self.diff.clone() {
+            let (sx_data, rx_data): (Sender<Option<DataWithInfo>>, Receiver<Option<DataWithInfo>>) = unbounded();
+            let result = self.walk_diff(&base, &head, sx_data, sx_error);
+            if result.is_ok() {
+                self.process(rx_data, &head);
+            }
+            let _ = self.sx_input.send(None);
+            let _ = self.source.flush();
+
+            return result.and_then(|_| walk_error(&rx_error));
+        }
+
+        if self.history {
+            let branches_to_walk = match &self.branches {
+                Some(branches) => branches.iter().cloned().collect::<Vec<String>>(),
+                None => branches_to_scan.into_iter().filter(|b| b != FILE_SYSTEM).collect::<Vec<String>>(),
+            };
+            let (sx_data, rx_data): (Sender<Option<DataWithInfo>>, Receiver<Option<DataWithInfo>>) = unbounded();
+            let result = self.walk_history(&branches_to_walk, sx_data, sx_error);
+            if result.is_ok() {
+                self.process(rx_data, GIT_HISTORY);
+            }
+            let _ = self.sx_input.send(None);
+            let _ = self.source.flush();
+
+            return result.and_then(|_| walk_error(&rx_error));
+        }
+
+        let mut result = Ok(());
         for branch in branches_to_scan.iter() {
             let (sx_data, rx_data): (Sender<Option<DataWithInfo>>, Receiver<Option<DataWithInfo>>) = unbounded();
             if branch == FILE_SYSTEM {
-                self.walk_dir(sx_data)?;
-                self.process(rx_data, &branch);
+                result = self.walk_dir(sx_data, sx_error.clone());
+                if result.is_ok() {
+                    self.process(rx_data, branch);
+                }
                 break;
             }
             if let Some(branches) = &self.branches {
@@ -131,52 +257,143 @@ impl Executor {
                     continue;
                 }
             }
-            match self.source.switch_branch(branch) {
-                Ok(()) => (),
-                Err(_) => {
-                    continue;
-                },
-            };
-            let branch = branch.to_string().clone();
-            self.walk_dir(sx_data)?;
-            self.process(rx_data, &branch);
+            result = self.walk_tree(branch, sx_data, sx_error.clone());
+            if result.is_err() {
+                break;
+            }
+            self.process(rx_data, branch);
         }
 
         let _ = self.sx_input.send(None);
         let _ = self.source.flush();
 
-        Ok(())
+        result.and_then(|_| walk_error(&rx_error))
     }
 
+    /// Walks the directory in a separate thread sending files to inspect,
+    /// files that cannot be read are sent to the error channel and the walk continues.
+    ///
     #[inline(always)]
-    fn walk_dir(&self, sx: Sender<Option<DataWithInfo>>) -> Result<(), ExecutorError>{
-        let Some(walk_dir) = self.source.walk_dir() else {
-            let _ = self.sx_input.send(None);
+    fn walk_dir(&self, sx: Sender<Option<DataWithInfo>>, sx_error: Sender<ExecutorError>) -> Result<(), ExecutorError>{
+        let (Some(walk_dir), Some(root)) = (self.source.walk_dir(), self.source.path_buf()) else {
             return Err(ExecutorError::Unexpected("unable to walk directory".to_string()));
         };
 
-        let omit = self.omit.clone();
-        let decompress = self.decompress;
+        let mut filter = self.filter.clone();
+        let extractor = self.extractor;
+        let max_size = self.max_read_size();
+        let sx_input = self.sx_input.clone();
         let read_binary = self.scan_binary;
 
         spawn( move || {
-            'walker: for entry in walk_dir {
-                let Ok(entry) = entry else {
-                    continue 'walker;
+            let mut walker = walk_dir.into_iter();
+            'walker: while let Some(entry) = walker.next() {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(e) => {
+                        let _ = sx_error.send(std::io::Error::from(e).into());
+                        continue 'walker;
+                    },
                 };
-                if let Some(dir_name) = entry.path().to_str() {
-                    for pattern in omit.iter() {
-                        if dir_name.contains(pattern) {
-                            continue 'walker;
-                        }
+                let relative = match entry.depth() {
+                    0 => entry.file_name().to_str(),
+                    _ => entry.path().strip_prefix(&root).ok().and_then(|p| p.to_str()),
+                }.unwrap_or_default().to_string();
+                if entry.file_type().is_dir() {
+                    if entry.depth() > 0 && filter.is_excluded(&relative, true) {
+                        walker.skip_current_dir();
+                        continue 'walker;
                     }
+                    filter.read_ignore_files(entry.path(), if entry.depth() > 0 { &relative } else { "" });
+                    continue 'walker;
                 }
-                if entry.file_type().is_dir() {
+                if filter.is_excluded(&relative, false) {
+                    continue 'walker;
+                }
+
+                if entry.metadata().is_ok_and(|m| m.len() > max_size) {
+                    skip(&sx_input, entry.path().to_str().unwrap_or_default(), SkipReason::FileSize);
                     continue 'walker;
                 }
 
                 let entry = entry.into_path();
-                let _ = extract_utf8_and_send(&sx, &entry, decompress, read_binary); // TODO: Introduce error channel.
+                if let Err(e) = extract_utf8_and_send(&sx, &sx_input, &entry, extractor, read_binary) {
+                    let _ = sx_error.send(e);
+                }
+            }
+            let _ = sx.send(None);
+        });
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn walk_tree(&self, branch: &str, sx: Sender<Option<DataWithInfo>>, sx_error: Sender<ExecutorError>) -> Result<(), ExecutorError> {
+        let tree = self.source.tree(branch)?;
+        let filter = self.filter.clone();
+        let extractor = self.extractor;
+        let max_size = self.max_read_size();
+        let sx_input = self.sx_input.clone();
+        let read_binary = self.scan_binary;
+
+        spawn(move || {
+            let result = tree.walk(&filter, |blob| {
+                if blob.content.len() as u64 > max_size {
+                    skip(&sx_input, &blob.file, SkipReason::FileSize);
+                    return;
+                }
+                extract_blob_and_send(&sx, &sx_input, blob, extractor, read_binary);
+            });
+            if let Err(e) = result {
+                let _ = sx_error.send(e.into());
+            }
+            let _ = sx.send(None);
+        });
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn walk_history(&self, branches: &[String], sx: Sender<Option<DataWithInfo>>, sx_error: Sender<ExecutorError>) -> Result<(), ExecutorError> {
+        let history = self.source.history(branches, &self.range)?;
+        let filter = self.filter.clone();
+
+        spawn(move || {
+            let result = history.walk(&filter, |chunk| send_chunk(&sx, chunk));
+            if let Err(e) = result {
+                let _ = sx_error.send(e.into());
+            }
+            let _ = sx.send(None);
+        });
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn walk_diff(&self, base: &str, head: &str, sx: Sender<Option<DataWithInfo>>, sx_error: Sender<ExecutorError>) -> Result<(), ExecutorError> {
+        let diff = self.source.diff(base, head)?;
+        let filter = self.filter.clone();
+
+        spawn(move || {
+            let result = diff.walk(&filter, |chunk| send_chunk(&sx, chunk));
+            if let Err(e) = result {
+                let _ = sx_error.send(e.into());
+            }
+            let _ = sx.send(None);
+        });
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn walk_staged(&self, sx: Sender<Option<DataWithInfo>>, sx_error: Sender<ExecutorError>) -> Result<(), ExecutorError> {
+        let staged = self.source.staged()?;
+        let filter = self.filter.clone();
+
+        spawn(move || {
+            let result = staged.walk(&filter, |chunk| send_chunk(&sx, chunk));
+            if let Err(e) = result {
+                let _ = sx_error.send(e.into());
             }
             let _ = sx.send(None);
         });
@@ -184,6 +401,16 @@ impl Executor {
         Ok(())
     }
 
+    /// Returns the maximum size of the file to read, archives are read up to the archive size limit to be extracted.
+    ///
+    #[inline(always)]
+    fn max_read_size(&self) -> u64 {
+        match self.extractor {
+            Some(_) => self.limits.max_file_size.max(self.limits.max_archive_size),
+            None => self.limits.max_file_size,
+        }
+    }
+
     #[inline(always)]
     fn process(&mut self, rx: Receiver<Option<DataWithInfo>>, branch: &str) {
         rx.into_iter().par_bridge().for_each( |input| {
@@ -194,82 +421,74 @@ impl Executor {
             let sx_input = self.sx_input.clone();
             let branch = branch.to_string().clone();
             let _ = sx_input.send(Some(Input::Bytes(input.data.as_bytes().len())));
-            inspector.inspect(&input.data, &input.file_name, &branch);
+            let _ = sx_input.send(Some(Input::File(input.file_name.clone())));
+            inspector.inspect(&input.data, &input.file_name, &branch, &input.origin);
         });
     }
 }
 
+/// Returns the first error sent by the walker, the walker sends errors before it closes the data channel,
+/// so all of them are received once the data is processed.
+///
 #[inline(always)]
-fn extract_utf8_and_send(sx: &Sender<Option<DataWithInfo>>, path: &PathBuf, decompress: bool, scan_binary: bool) -> Result<(), ExecutorError> {
-    let file_name = path.as_path().to_str().unwrap_or_default().to_string();
-    if decompress {
-        return match &file_name[file_name.len()-3..file_name.len()] {
-            "tar" | ".gz" => decomopress_tar_archive_and_send(sx, path, file_name),
-            "zip" | "jar" | "bz2" => decomopress_zip_archive_and_send(sx, path, file_name),
-            "zst" | "rar" | "iso" | ".rz" | ".7z" | "s7z" | "aar" | "apk" => Ok(()),
-            _ => read_and_send(sx, path, file_name, scan_binary),
-        }
+fn walk_error(rx_error: &Receiver<ExecutorError>) -> Result<(), ExecutorError> {
+    match rx_error.try_recv() {
+        Ok(e) => Err(e),
+        Err(_) => Ok(()),
     }
-    read_and_send(sx, path, file_name, scan_binary)
 }
 
 #[inline(always)]
-fn read_and_send(sx: &Sender<Option<DataWithInfo>>, path: &PathBuf, file_name: String, scan_binary: bool) -> Result<(), ExecutorError> {
-    match scan_binary {
-        true => read_binaryfile_and_send_to_channel(sx, path, file_name),
-        false => read_textfile_and_send_to_channel(sx, path, file_name),
-    }
+fn send_chunk(sx: &Sender<Option<DataWithInfo>>, chunk: Chunk) {
+    let _ = sx.send(Some(DataWithInfo {
+        data: chunk.data,
+        file_name: chunk.file,
+        origin: Origin { commit: chunk.commit, lines: Some(chunk.lines) },
+    }));
 }
 
 #[inline(always)]
-fn read_textfile_and_send_to_channel(sx: &Sender<Option<DataWithInfo>>, path: &PathBuf, file_name: String) -> Result<(), ExecutorError> {
-    let data = read_to_string(path)?;
-    let _ = sx.send(Some(DataWithInfo{data , file_name}));
+fn extract_utf8_and_send(sx: &Sender<Option<DataWithInfo>>, sx_input: &Sender<Option<Input>>, path: &PathBuf, extractor: Option<Extractor>, scan_binary: bool) -> Result<(), ExecutorError> {
+    let file_name = path.as_path().to_str().unwrap_or_default().to_string();
+    if let Some(extractor) = extractor {
+        extractor.extract(&file_name, read(path)?, &mut |extracted| extracted_and_send(sx, sx_input, extracted, scan_binary));
+        return Ok(());
+    }
+    bytes_and_send(sx, sx_input, read(path)?, file_name, scan_binary);
 
     Ok(())
 }
 
 #[inline(always)]
-fn read_binaryfile_and_send_to_channel(sx: &Sender<Option<DataWithInfo>>, path: &PathBuf, file_name: String) -> Result<(), ExecutorError> {
-    let bytes = read(&path)?;
-    let data = String::from_utf8_lossy(&bytes).to_string();
-    let _ = sx.send(Some(DataWithInfo{data , file_name}));
-
-    Ok(())
+fn extract_blob_and_send(sx: &Sender<Option<DataWithInfo>>, sx_input: &Sender<Option<Input>>, blob: Blob, extractor: Option<Extractor>, scan_binary: bool) {
+    if let Some(extractor) = extractor {
+        extractor.extract(&blob.file, blob.content, &mut |extracted| extracted_and_send(sx, sx_input, extracted, scan_binary));
+        return;
+    }
+    bytes_and_send(sx, sx_input, blob.content, blob.file, scan_binary);
 }
 
 #[inline(always)]
-fn decomopress_zip_archive_and_send(sx: &Sender<Option<DataWithInfo>>, path: &PathBuf, file_name: String) -> Result<(), ExecutorError> {
-    let file = File::open(path)?;
-    let mut zip = ZipArchive::new(&file)?;
-
-    for i in 0..zip.len() {
-        let mut data = String::new();
-        let mut file = zip.by_index(i)?;
-        let file_name = format!("{}/{}", file_name, file.name());
-        let Ok(_) = file.read_to_string(&mut data) else {
-            continue;
-        };
-        let _ = sx.send(Some(DataWithInfo{data, file_name}));
+fn extracted_and_send(sx: &Sender<Option<DataWithInfo>>, sx_input: &Sender<Option<Input>>, extracted: Extracted, scan_binary: bool) {
+    match extracted {
+        Extracted::File(file_name, content) => bytes_and_send(sx, sx_input, content, file_name, scan_binary),
+        Extracted::Skipped(file_name, reason) => skip(sx_input, &file_name, reason),
     }
-
-    Ok(())
 }
 
 #[inline(always)]
-fn decomopress_tar_archive_and_send(sx: &Sender<Option<DataWithInfo>>, path: &PathBuf, file_name: String) -> Result<(), ExecutorError> {
-    let file = File::open(path)?;
-    let mut t = Archive::new(&file);
-
-    for file in t.entries()? {
-        let mut file = file?;
-        let mut data = String::new();
-        let file_name = format!("{}/{}", file_name, file.path().unwrap_or_default().to_str().unwrap_or_default());
-        let Ok(_) = file.read_to_string(&mut data) else {
-            continue;
-        };
-        let _ = sx.send(Some(DataWithInfo{data, file_name}));
-    }
+fn skip(sx_input: &Sender<Option<Input>>, file: &str, reason: SkipReason) {
+    let _ = sx_input.send(Some(Input::Skipped(Skip { file: file.to_string(), reason })));
+}
 
-    Ok(())
+/// Sends the text of the content to inspect, binary content is sent as extracted strings if binary files are scanned and skipped otherwise.
+///
+#[inline(always)]
+fn bytes_and_send(sx: &Sender<Option<DataWithInfo>>, sx_input: &Sender<Option<Input>>, content: Vec<u8>, file_name: String, scan_binary: bool) {
+    match text(content, scan_binary) {
+        Some(data) => {
+            let _ = sx.send(Some(DataWithInfo{data, file_name, origin: Origin::default()}));
+        },
+        None => skip(sx_input, &file_name, SkipReason::Binary),
+    };
 }
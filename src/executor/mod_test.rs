@@ -0,0 +1,148 @@
+use crate::executor::{Config, Executor};
+
+mod tests {
+    use crate::archive::Limits;
+    use crate::reporter::{new as new_reporter, Format, Input, Output, Redaction, Reporter};
+    use crate::source::{BranchLevel, DataSource, git::CommitRange};
+    use crossbeam_channel::unbounded;
+    use git2::{Repository, Signature};
+    use random_string::generate;
+    use std::{env::temp_dir, fs::{create_dir_all, remove_dir_all, write}, path::PathBuf};
+
+    use super::*;
+
+    const CHARSET: &str = "abcdefghijklmnopqrstuwxyz_";
+    const CONFIG: &str = "- name: Password\n  secret_regexes:\n    - password = \"[A-Za-z0-9]+\"\n";
+
+    /// Fixture is the temporary origin repository with a committed secret and the scanner config, removed when dropped.
+    ///
+    struct Fixture {
+        dir: PathBuf,
+        origin: String,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let dir = temp_dir().join(format!("rogue_test_executor_{}", generate(12, CHARSET)));
+            let origin = dir.join("origin");
+            let repo = Repository::init(&origin).expect("init repository");
+            create_dir_all(origin.join("config")).expect("create directory");
+            write(origin.join("config/app.env"), "user = admin\npassword = \"Zx9kLm2Qw\"\n").expect("write file");
+            let mut index = repo.index().expect("open index");
+            index.add_path(&PathBuf::from("config/app.env")).expect("add file to index");
+            index.write().expect("write index");
+            let tree = repo.find_tree(index.write_tree().expect("write tree")).expect("find tree");
+            let signature = Signature::now("Test Author", "test@author.com").expect("signature");
+            repo.commit(Some("HEAD"), &signature, &signature, "test commit", &tree, &[]).expect("commit");
+            write(dir.join("config.yaml"), CONFIG).expect("write config");
+
+            Self { origin: origin.to_str().unwrap_or_default().to_string(), dir }
+        }
+
+        fn path(&self, file: &str) -> PathBuf {
+            self.dir.join(file)
+        }
+
+        /// Scans the fresh clone of the origin and returns the inputs sent to the reporter.
+        ///
+        fn scan(&self, allowlist: Option<&PathBuf>, baseline: Option<&PathBuf>, write_baseline: Option<&PathBuf>) -> Vec<Input> {
+            let (sx_input, rx_input) = unbounded();
+            let config = self.path("config.yaml");
+            let mut executor = Executor::new(&Config {
+                data_source: DataSource::Git,
+                path: None,
+                url: Some(&self.origin),
+                config: Some(&config),
+                fingerprints: None,
+                allowlist,
+                baseline,
+                write_baseline,
+                include: None,
+                omit: None,
+                nodeps: false,
+                ignore_files: true,
+                branch_level: BranchLevel::Head,
+                branches: &None,
+                sx_input,
+                decompress: false,
+                limits: Limits::default(),
+                scan_binary: false,
+                history: false,
+                range: CommitRange::default(),
+                diff_base: None,
+                diff_head: None,
+                staged: false,
+                context_lines: 0,
+            }).expect("executor");
+            executor.execute().expect("scan");
+
+            rx_input.try_iter().flatten().collect()
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = remove_dir_all(&self.dir);
+        }
+    }
+
+    fn findings(inputs: &[Input]) -> Vec<String> {
+        inputs.iter().filter_map(|input| match input {
+            Input::Finding(f) => Some(f.file.clone()),
+            _ => None,
+        }).collect()
+    }
+
+    fn suppressed(inputs: &[Input]) -> usize {
+        inputs.iter().map(|input| match input {
+            Input::Suppressed(count) => *count,
+            _ => 0,
+        }).sum()
+    }
+
+    #[test]
+    fn it_should_suppress_findings_of_another_clone_with_baseline() {
+        let fixture = Fixture::new();
+        let baseline = fixture.path("baseline.json");
+
+        let first = fixture.scan(None, None, Some(&baseline));
+        assert_eq!(findings(&first).len(), 1);
+        let written = std::fs::read_to_string(&baseline).expect("read baseline");
+        assert!(written.contains("\"file\": \"config/app.env\""), "{}", written);
+
+        let second = fixture.scan(None, Some(&baseline), None);
+        assert_eq!(findings(&second), Vec::<String>::new());
+        assert_eq!(suppressed(&second), 1);
+    }
+
+    #[test]
+    fn it_should_suppress_findings_matching_allowlist_path_relative_to_clone() {
+        let fixture = Fixture::new();
+        let allowlist = fixture.path("allowlist.yaml");
+        write(&allowlist, "paths:\n  - config/*.env\n").expect("write allowlist");
+
+        let inputs = fixture.scan(Some(&allowlist), None, None);
+        assert_eq!(findings(&inputs), Vec::<String>::new());
+        assert_eq!(suppressed(&inputs), 1);
+    }
+
+    #[test]
+    fn it_should_locate_sarif_artifacts_relative_to_clone() {
+        let fixture = Fixture::new();
+        let (sx_input, rx_input) = unbounded();
+        for input in fixture.scan(None, None, None) {
+            let _ = sx_input.send(Some(input));
+        }
+        let _ = sx_input.send(None);
+        let (sx_output, rx_output) = unbounded();
+        let mut reporter = new_reporter(Output::Receiver(sx_output), Format::Sarif, 0, Redaction::None);
+        reporter.receive(rx_input);
+        drop(reporter);
+        let sarif = rx_output.into_iter().map_while(|s| s).collect::<String>();
+
+        let log = serde_json::from_str::<serde_json::Value>(&sarif).expect("sarif log");
+        let location = &log["runs"][0]["results"][0]["locations"][0]["physicalLocation"]["artifactLocation"];
+        assert_eq!(location["uri"], "config/app.env");
+        assert_eq!(location["uriBaseId"], "%SRCROOT%");
+    }
+}
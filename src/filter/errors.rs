@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+/// FilterError describes all errors that can occure in PathFilter.
+///
+#[derive(Error, Debug)]
+pub enum FilterError {
+    #[error("failed to build path filter, Glob failed with: {0}")]
+    GlobBuilderFailure(#[from] globset::Error),
+}
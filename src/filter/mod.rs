@@ -0,0 +1,200 @@
+pub mod errors;
+
+use errors::FilterError;
+use globset::{escape, GlobBuilder, GlobMatcher};
+use std::fs::read_to_string;
+use std::path::Path;
+
+#[cfg(test)]
+mod mod_test;
+
+/// Ignore files honored in the scanned directories, patterns of the file apply to the directory the file is placed in.
+/// Files later on the list take precedence.
+///
+pub const IGNORE_FILES: [&str; 3] = [".gitignore", ".ignore", ".rogueignore"];
+
+const DEPENDENCIES: &[&str] = &[".npm/", ".npmrc/", ".git/", "venv/", "virtualenv/", ".gem/", "target/", "bin/", ".DS_Store/"];
+
+/// Preset is the named set of exclude patterns.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    Dependencies,
+}
+
+impl Preset {
+    #[inline(always)]
+    pub fn patterns(&self) -> &'static [&'static str] {
+        match self {
+            Self::Dependencies => DEPENDENCIES,
+        }
+    }
+}
+
+/// Rule is a single pattern in the gitignore syntax.
+/// Pattern without a slash matches at any depth, pattern with a slash is anchored to the base directory,
+/// pattern ending with a slash matches only directories and pattern starting with `!` negates the match.
+///
+#[derive(Debug, Clone)]
+struct Rule {
+    matcher: GlobMatcher,
+    negated: bool,
+    dir_only: bool,
+}
+
+impl Rule {
+    /// Parses the line of the ignore file relative to the base directory, empty lines and comments have no rule.
+    ///
+    #[inline(always)]
+    fn parse(base: &str, line: &str) -> Result<Option<Self>, FilterError> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(None);
+        }
+        let (negated, pattern) = match line.strip_prefix('!') {
+            Some(p) => (true, p),
+            None => (false, line),
+        };
+        let pattern = pattern.strip_prefix('\\').filter(|p| p.starts_with(['#', '!'])).unwrap_or(pattern);
+        let (dir_only, pattern) = match pattern.strip_suffix('/') {
+            Some(p) => (true, p),
+            None => (false, pattern),
+        };
+        if pattern.is_empty() {
+            return Ok(None);
+        }
+        let glob = match pattern.strip_prefix('/') {
+            Some(p) => format!("{base}{p}"),
+            None if pattern.contains('/') => format!("{base}{pattern}"),
+            None => format!("{base}**/{pattern}"),
+        };
+        let matcher = GlobBuilder::new(&glob).literal_separator(true).backslash_escape(true).build()?.compile_matcher();
+
+        Ok(Some(Self { matcher, negated, dir_only }))
+    }
+}
+
+/// PathFilter decides which paths are scanned.
+/// Path is excluded when it or any of its parent directories matches exclude patterns, presets or ignore files,
+/// last matching rule wins so ignore files can negate patterns of the parent directory.
+/// When include patterns are given only files matching any of them, or placed in the matching directory, are scanned.
+/// Paths are relative to the scanned root and separated with `/`.
+///
+#[derive(Debug, Clone, Default)]
+pub struct PathFilter {
+    include: Vec<Rule>,
+    exclude: Vec<Rule>,
+    ignore: Vec<Rule>,
+    ignore_files: bool,
+}
+
+impl PathFilter {
+    /// Creates the filter that honors ignore files if requested.
+    ///
+    #[inline(always)]
+    pub fn new(ignore_files: bool) -> Self {
+        Self { ignore_files, ..Self::default() }
+    }
+
+    /// Adds the pattern of files to include.
+    ///
+    #[inline(always)]
+    pub fn with_include(&mut self, pattern: &str) -> Result<(), FilterError> {
+        if let Some(rule) = Rule::parse("", pattern)? {
+            self.include.push(rule);
+        }
+        Ok(())
+    }
+
+    /// Adds the pattern of paths to exclude.
+    ///
+    #[inline(always)]
+    pub fn with_exclude(&mut self, pattern: &str) -> Result<(), FilterError> {
+        if let Some(rule) = Rule::parse("", pattern)? {
+            self.exclude.push(rule);
+        }
+        Ok(())
+    }
+
+    /// Adds the patterns of the preset to exclude.
+    ///
+    #[inline(always)]
+    pub fn with_preset(&mut self, preset: Preset) -> Result<(), FilterError> {
+        for pattern in preset.patterns() {
+            self.with_exclude(pattern)?;
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn honors_ignore_files(&self) -> bool {
+        self.ignore_files
+    }
+
+    /// Adds the content of the ignore file placed in the directory, the root directory is empty.
+    /// Lines that aren't valid patterns are skipped. Does nothing if the filter doesn't honor ignore files.
+    ///
+    #[inline(always)]
+    pub fn add_ignore_file(&mut self, dir: &str, content: &str) {
+        if !self.ignore_files {
+            return;
+        }
+        let base = match dir.trim_end_matches('/') {
+            "" => String::new(),
+            dir => format!("{}/", escape(dir)),
+        };
+        self.ignore.extend(content.lines().filter_map(|line| Rule::parse(&base, line).ok().flatten()));
+    }
+
+    /// Reads the ignore files from the directory on the filesystem, dir is the path of the directory relative to the root.
+    ///
+    #[inline(always)]
+    pub fn read_ignore_files(&mut self, path: &Path, dir: &str) {
+        if !self.ignore_files {
+            return;
+        }
+        for name in IGNORE_FILES {
+            if let Ok(content) = read_to_string(path.join(name)) {
+                self.add_ignore_file(dir, &content);
+            }
+        }
+    }
+
+    /// Checks if the path shall not be scanned, excluded directories shall not be descended into.
+    ///
+    #[inline(always)]
+    pub fn is_excluded(&self, path: &str, is_dir: bool) -> bool {
+        let path = path.trim_end_matches('/');
+        if path.match_indices('/').any(|(idx, _)| self.is_ignored(&path[..idx], true)) {
+            return true;
+        }
+        if self.is_ignored(path, is_dir) {
+            return true;
+        }
+
+        !is_dir && !self.is_included(path)
+    }
+
+    #[inline(always)]
+    fn is_ignored(&self, path: &str, is_dir: bool) -> bool {
+        matches(&self.exclude, path, is_dir) || matches(&self.ignore, path, is_dir)
+    }
+
+    #[inline(always)]
+    fn is_included(&self, path: &str) -> bool {
+        self.include.is_empty()
+            || matches(&self.include, path, false)
+            || path.match_indices('/').any(|(idx, _)| matches(&self.include, &path[..idx], true))
+    }
+}
+
+/// Checks if the last rule matching the path isn't negated.
+///
+#[inline(always)]
+fn matches(rules: &[Rule], path: &str, is_dir: bool) -> bool {
+    rules
+        .iter()
+        .rev()
+        .find(|r| (is_dir || !r.dir_only) && r.matcher.is_match(path))
+        .is_some_and(|r| !r.negated)
+}
@@ -0,0 +1,68 @@
+use crate::filter::{PathFilter, Preset};
+
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_exclude_whole_path_segments_instead_of_substrings() {
+        let mut filter = PathFilter::new(false);
+        let _ = filter.with_exclude("bin/");
+        let _ = filter.with_exclude("*.min.js");
+
+        assert!(filter.is_excluded("bin", true));
+        assert!(filter.is_excluded("bin/tool", false));
+        assert!(filter.is_excluded("src/bin/main.rs", false));
+        assert!(filter.is_excluded("web/app.min.js", false));
+        assert!(!filter.is_excluded("cabin", true));
+        assert!(!filter.is_excluded("cabin/config.yaml", false));
+        assert!(!filter.is_excluded("bin", false));
+    }
+
+    #[test]
+    fn it_should_include_only_matching_files() {
+        let mut filter = PathFilter::new(false);
+        let _ = filter.with_include("/src/");
+        let _ = filter.with_include("*.env");
+
+        assert!(!filter.is_excluded("src/main.rs", false));
+        assert!(!filter.is_excluded("deploy/prod.env", false));
+        assert!(!filter.is_excluded("docs", true));
+        assert!(filter.is_excluded("docs/README.md", false));
+        assert!(filter.is_excluded("lib/src/a.rs", false));
+    }
+
+    #[test]
+    fn it_should_honor_ignore_files_relative_to_their_directory() {
+        let mut filter = PathFilter::new(true);
+        filter.add_ignore_file("", "# build output\n/dist\n*.log\nfixtures/\n");
+        filter.add_ignore_file("app", "!keep.log\nlocal/*.json\n");
+
+        assert!(filter.is_excluded("dist", true));
+        assert!(!filter.is_excluded("app/dist", true));
+        assert!(filter.is_excluded("app/debug.log", false));
+        assert!(!filter.is_excluded("app/keep.log", false));
+        assert!(filter.is_excluded("keep.log", false));
+        assert!(filter.is_excluded("app/local/creds.json", false));
+        assert!(!filter.is_excluded("local/creds.json", false));
+        assert!(filter.is_excluded("test/fixtures/keep.log", false));
+    }
+
+    #[test]
+    fn it_should_not_honor_ignore_files_when_disabled() {
+        let mut filter = PathFilter::new(false);
+        filter.add_ignore_file("", ".env\n");
+
+        assert!(!filter.is_excluded(".env", false));
+    }
+
+    #[test]
+    fn it_should_exclude_dependencies_preset() {
+        let mut filter = PathFilter::new(false);
+        let _ = filter.with_preset(Preset::Dependencies);
+
+        assert!(filter.is_excluded("web/node/.npm/cache/index", false));
+        assert!(filter.is_excluded("target", true));
+        assert!(filter.is_excluded(".git/config", false));
+        assert!(!filter.is_excluded("src/target.rs", false));
+    }
+}
@@ -1,4 +1,5 @@
 use thiserror::Error;
+use crate::decoders::errors::DecoderError;
 use crate::detectors::errors::DetectorError;
 
 /// InspectorError describes all errors that can occure in Inspector.
@@ -7,4 +8,8 @@ use crate::detectors::errors::DetectorError;
 pub enum InspectorError {
     #[error("failed to build inspector, detector failed with: {0}")]
     BuildFailure(#[from] DetectorError),
+    #[error("failed to build inspector, decoder failed with: {0}")]
+    DecoderBuildFailure(#[from] DecoderError),
+    #[error("failed to build inspector, regex failed with: {0}")]
+    RegexBuildFailure(#[from] regex::Error),
 }
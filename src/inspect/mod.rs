@@ -1,15 +1,31 @@
 pub mod errors;
 
-use crate::detectors::{Scanner, regex::{Schema, Pattern}};
-use crossbeam_channel::Sender;
+use crate::allowlist::{Allowlist, suppressed_lines};
+use crate::baseline::{Baseline, BaselineEntry, Recorder};
+use crate::decoders::{Decoded, Decoders, jwt::JwtDecoder};
+use crate::detectors::{Scanner, fingerprint::Lab, lexer::{Language, Lexer}, regex::{Schema, Pattern}};
+use crossbeam_channel::{unbounded, Sender};
 use errors::InspectorError;
 use crate::lines::LinesEnds;
 use crate::reporter::Input;
-use std::path::Path;
+use crate::result::{mask, Commit, Context, DecoderType, Rule, Secret};
+use crate::structured::{Entry, Format};
+use regex::Regex;
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod mod_test;
+
+const ENCODED_RUN: &str = r"[A-Za-z0-9+/=_\-]{16,}";
+const CONTEXT_MAX_LINE_LEN: usize = 256;
 
 #[derive(Debug)]
 enum ScannerWrapper {
     Regex(Pattern),
+    Lexer(Lexer),
+    Fingerprint(Lab),
+    Jwt(JwtDecoder),
 }
 
 impl Scanner for ScannerWrapper {
@@ -17,16 +33,100 @@ impl Scanner for ScannerWrapper {
     fn scan(&self, lines_ends: &impl crate::lines::LinesEndsProvider, s: &str, file: &str, branch: &str, sx: Sender<Option<Input>>) {
         match self {
             Self::Regex(scan) => scan.scan(lines_ends, s, file, branch, sx),
+            Self::Lexer(scan) => scan.scan(lines_ends, s, file, branch, sx),
+            Self::Fingerprint(scan) => scan.scan(lines_ends, s, file, branch, sx),
+            Self::Jwt(scan) => scan.scan(lines_ends, s, file, branch, sx),
+        }
+    }
+}
+
+impl ScannerWrapper {
+    #[inline(always)]
+    fn has_key_paths(&self) -> bool {
+        match self {
+            Self::Regex(scan) => scan.has_key_paths(),
+            Self::Lexer(_) | Self::Fingerprint(_) | Self::Jwt(_) => false,
+        }
+    }
+
+    /// Checks if the scanner is replaced by the lexer in source files.
+    ///
+    #[inline(always)]
+    fn uses_lexer(&self) -> bool {
+        match self {
+            Self::Regex(scan) => scan.uses_lexer(),
+            Self::Lexer(_) | Self::Fingerprint(_) | Self::Jwt(_) => false,
+        }
+    }
+
+    #[inline(always)]
+    fn scan_entries(&self, entries: &[Entry], file: &str, branch: &str, sx: Sender<Option<Input>>) {
+        match self {
+            Self::Regex(scan) => scan.scan_entries(entries, file, branch, sx),
+            Self::Lexer(_) | Self::Fingerprint(_) | Self::Jwt(_) => (),
+        }
+    }
+}
+
+/// Attributor resolves the commit that introduced the line of the file on the given branch.
+///
+pub trait Attributor: Debug + Send + Sync {
+    fn attribute(&self, branch: &str, file: &str, line: usize) -> Option<Commit>;
+}
+
+/// Origin describes where the inspected data comes from.
+/// If lines are specified, each line of the inspected data is mapped to the line in the original file.
+/// If commit is specified, all findings are attributed to it, otherwise the attributor is asked.
+///
+#[derive(Debug, Default, Clone)]
+pub struct Origin {
+    pub commit: Option<Commit>,
+    pub lines: Option<Vec<usize>>,
+}
+
+impl Origin {
+    #[inline(always)]
+    fn apply(&self, secret: &mut Secret, attributor: Option<&dyn Attributor>) {
+        if let Some(lines) = &self.lines {
+            secret.line = match secret.line {
+                0 => 0,
+                l => lines.get(l - 1).copied().unwrap_or_default(),
+            };
+        }
+        if secret.commit.is_some() {
+            return;
+        }
+        let commit = match &self.commit {
+            Some(commit) => Some(commit.clone()),
+            None => attributor.and_then(|a| a.attribute(&secret.branch, &secret.file, secret.line)),
+        };
+        if let Some(commit) = commit {
+            secret.attribute(commit);
         }
     }
 }
 
 /// Inspector holds collection of detectors to be use for scanning.
 /// Performs pre-processing of the given input before sending it to scanners.
+/// Encoded runs of the input are decoded and scanned again, findings in them are tagged with the decoder type.
+/// Structured files, like JSON, YAML or TOML, are parsed to entries matched against key paths of the schemas.
+/// Source files are scanned by the lexer matching names string literals are assigned to against keys of the schemas with the lexer option,
+/// those schemas aren't matched with regexes over the source, so words in comments and docs are not reported.
+/// Findings allowed by the allowlist, suppressed with inline comments or known in the baseline are counted but not reported.
+/// If context is requested, findings carry the surrounding lines with the secret masked.
 ///
 #[derive(Debug)]
 pub struct Inspector {
     scanners: Vec<ScannerWrapper>,
+    decoders: Decoders,
+    attributor: Option<Box<dyn Attributor>>,
+    allowlist: Option<Allowlist>,
+    baseline: Option<Baseline>,
+    recorder: Option<Recorder>,
+    root: Option<PathBuf>,
+    context_lines: usize,
+    encoded_run: Regex,
+    structured: bool,
     sx: Sender<Option<Input>>
 }
 
@@ -35,25 +135,243 @@ impl Inspector {
     pub fn try_new(path_to_config_yaml: &str, sx: Sender<Option<Input>>) -> Result<Self, InspectorError> {
         let path = Path::new(path_to_config_yaml);
         let mut scanners: Vec<ScannerWrapper> = Vec::new();
+        let mut rules: Vec<Rule> = Vec::new();
+        let mut lexer_patterns: Vec<Pattern> = Vec::new();
         for schema in Schema::read_from_yaml_file(path)?.iter() {
-            let s: ScannerWrapper = ScannerWrapper::Regex(schema.try_into()?);
-            scanners.push(s);
+            let pattern: Pattern = schema.try_into()?;
+            if pattern.uses_lexer() {
+                lexer_patterns.push(schema.try_into()?);
+            }
+            scanners.push(ScannerWrapper::Regex(pattern));
+            rules.push(schema.rule());
         }
+        let lexer = Lexer::new(lexer_patterns);
+        if !lexer.is_empty() {
+            scanners.push(ScannerWrapper::Lexer(lexer));
+        }
+        let jwt = JwtDecoder::try_new()?;
+        rules.push(jwt.rule());
+        scanners.push(ScannerWrapper::Jwt(jwt));
+        let structured = scanners.iter().any(|s| s.has_key_paths());
         let _ = sx.send(Some(Input::Detectors(scanners.len())));
+        let _ = sx.send(Some(Input::Rules(rules)));
         Ok(Self {
             scanners,
+            decoders: Decoders::try_new()?,
+            attributor: None,
+            allowlist: None,
+            baseline: None,
+            recorder: None,
+            root: None,
+            context_lines: 0,
+            encoded_run: Regex::new(ENCODED_RUN)?,
+            structured,
             sx,
         })
     }
 
+    /// Adds the lab detecting secrets similar to fingerprinted suspects, its findings are reported with the fingerprint detector type.
+    ///
+    #[inline(always)]
+    pub fn with_fingerprints(&mut self, lab: Lab) -> &mut Self {
+        let _ = self.sx.send(Some(Input::Rules(lab.rules())));
+        self.scanners.push(ScannerWrapper::Fingerprint(lab));
+        let _ = self.sx.send(Some(Input::Detectors(self.scanners.len())));
+        self
+    }
+
+    /// Sets the attributor resolving authors of the findings.
+    ///
+    #[inline(always)]
+    pub fn with_attributor(&mut self, attributor: Box<dyn Attributor>) -> &mut Self {
+        self.attributor = Some(attributor);
+        self
+    }
+
+    /// Sets the allowlist of findings that shall not be reported.
+    ///
+    #[inline(always)]
+    pub fn with_allowlist(&mut self, allowlist: Allowlist) -> &mut Self {
+        self.allowlist = Some(allowlist);
+        self
+    }
+
+    /// Sets the baseline of known findings that shall not be reported.
+    ///
+    #[inline(always)]
+    pub fn with_baseline(&mut self, baseline: Baseline) -> &mut Self {
+        self.baseline = Some(baseline);
+        self
+    }
+
+    /// Starts recording fingerprints of all not suppressed findings, including those known in the baseline.
+    ///
+    #[inline(always)]
+    pub fn with_recorder(&mut self) -> &mut Self {
+        self.recorder = Some(Recorder::default());
+        self
+    }
+
+    /// Sets the scanned root, the allowlist, the baseline and the reporter use files relative to it.
+    ///
+    #[inline(always)]
+    pub fn with_root(&mut self, root: &Path) -> &mut Self {
+        let _ = self.sx.send(Some(Input::Root(root.to_path_buf())));
+        self.root = Some(root.to_path_buf());
+        self
+    }
+
+    /// Sets the number of lines before and after the line of the finding reported as the context of the finding.
+    ///
+    #[inline(always)]
+    pub fn with_context(&mut self, lines: usize) -> &mut Self {
+        self.context_lines = lines;
+        self
+    }
+
+    /// Returns the baseline of recorded findings if recording was started.
+    ///
+    #[inline(always)]
+    pub fn recorded_baseline(&self) -> Option<Baseline> {
+        self.recorder.as_ref().map(|r| r.baseline())
+    }
+
     #[inline(always)]
-    pub fn inspect(&self, s: &str, file: &str, branch: &str) {
+    pub fn inspect(&self, s: &str, file: &str, branch: &str, origin: &Origin) {
         // pre-process phase
         let line_ends = LinesEnds::from_str(s);
+        let (sx, rx) = unbounded();
 
         // scan phase
-        for scanner in self.scanners.iter() {
-            scanner.scan(&line_ends, s, file, branch, self.sx.clone());
+        for scanner in self.scanners_for(file) {
+            scanner.scan(&line_ends, s, file, branch, sx.clone());
+        }
+
+        // structured phase
+        if let (true, Some(format)) = (self.structured, Format::detect(file)) {
+            let entries = format.parse(s);
+            for scanner in self.scanners.iter() {
+                scanner.scan_entries(&entries, file, branch, sx.clone());
+            }
+        }
+
+        // decode phase
+        for decoded in self.decoders.decode(s) {
+            self.inspect_decoded(&decoded, file, branch, &sx);
+        }
+        drop(sx);
+
+        // post-process phase
+        let suppressed_lines = suppressed_lines(&line_ends, s);
+        let mut suppressed = 0;
+        for input in rx.iter() {
+            let Some(input) = input else {
+                continue;
+            };
+            let input = match input {
+                Input::Finding(mut secret) => {
+                    let relative_file = secret.relative_file(self.root.as_deref());
+                    if suppressed_lines.contains(&secret.line) || self.is_allowed(&secret, &relative_file) {
+                        suppressed += 1;
+                        continue;
+                    }
+                    let entry = BaselineEntry::new(&secret, &relative_file, s.lines().nth(secret.line.saturating_sub(1)).unwrap_or_default());
+                    secret.fingerprint = Some(entry.fingerprint.clone());
+                    if self.is_known(&entry) {
+                        suppressed += 1;
+                        continue;
+                    }
+                    let line = secret.line;
+                    origin.apply(&mut secret, self.attributor.as_deref());
+                    if self.context_lines > 0 {
+                        secret.context = self.context(s, &secret, line);
+                    }
+                    Input::Finding(secret)
+                },
+                other => other,
+            };
+            let _ = self.sx.send(Some(input));
+        }
+        if suppressed > 0 {
+            let _ = self.sx.send(Some(Input::Suppressed(suppressed)));
+        }
+    }
+
+    /// Returns scanners of the file, in source files the regex scanners of schemas matched by the lexer are left out.
+    ///
+    #[inline(always)]
+    fn scanners_for(&self, file: &str) -> impl Iterator<Item = &ScannerWrapper> {
+        let source = Language::detect(file).is_some();
+        self.scanners.iter().filter(move |scanner| !(source && scanner.uses_lexer()))
+    }
+
+    /// Takes the lines surrounding the given line of the input with the secret values masked.
+    /// Decoded values do not appear in the input, so all encoded runs in the line of the decoded secret are masked.
+    ///
+    #[inline(always)]
+    fn context(&self, s: &str, secret: &Secret, line: usize) -> Option<Context> {
+        if line == 0 {
+            return None;
+        }
+        let first = line.saturating_sub(self.context_lines).max(1);
+        let values = secret.values();
+        let encoded = !matches!(secret.decoder_type, DecoderType::Plane | DecoderType::Jwt);
+        let lines = s.lines()
+            .enumerate()
+            .skip(first - 1)
+            .take(line - first + self.context_lines + 1)
+            .map(|(idx, l)| {
+                let mut l = l.to_string();
+                for value in values.iter() {
+                    l = l.replace(value, &mask(value));
+                }
+                if encoded && idx + 1 == line {
+                    l = self.encoded_run.replace_all(&l, |c: &regex::Captures| mask(&c[0])).to_string();
+                }
+                l.chars().take(CONTEXT_MAX_LINE_LEN).collect()
+            })
+            .collect();
+
+        Some(Context { first_line: secret.line.saturating_sub(line - first), lines })
+    }
+
+    #[inline(always)]
+    fn is_known(&self, entry: &BaselineEntry) -> bool {
+        let known = match &self.baseline {
+            Some(baseline) => baseline.contains(&entry.fingerprint),
+            None => false,
+        };
+        if let Some(recorder) = &self.recorder {
+            recorder.record(entry.clone());
+        }
+
+        known
+    }
+
+    #[inline(always)]
+    fn is_allowed(&self, secret: &Secret, file: &str) -> bool {
+        match &self.allowlist {
+            Some(allowlist) => allowlist.is_allowed(secret, file),
+            None => false,
+        }
+    }
+
+    #[inline(always)]
+    fn inspect_decoded(&self, decoded: &Decoded, file: &str, branch: &str, sx: &Sender<Option<Input>>) {
+        let line_ends = LinesEnds::from_str(&decoded.data);
+        let (sx_decoded, rx_decoded) = unbounded();
+        for scanner in self.scanners_for(file) {
+            scanner.scan(&line_ends, &decoded.data, file, branch, sx_decoded.clone());
+        }
+        drop(sx_decoded);
+
+        for input in rx_decoded.iter() {
+            let Some(Input::Finding(mut secret)) = input else {
+                continue;
+            };
+            secret.decoder_type = decoded.decoder_type.clone();
+            secret.line = decoded.line;
+            let _ = sx.send(Some(Input::Finding(secret)));
         }
     }
 }
@@ -0,0 +1,42 @@
+use crate::inspect::{Inspector, Origin};
+
+mod tests {
+    use crate::reporter::Input;
+    use crossbeam_channel::unbounded;
+    use random_string::generate;
+    use std::{env::temp_dir, fs::{remove_file, write}};
+
+    use super::*;
+
+    const CHARSET: &str = "abcdefghijklmnopqrstuwxyz_";
+    const CONFIG: &str = "- name: Password\n  lexer: true\n  keys_with_secrets:\n    - keys: [password]\n      secrets: [\"[A-Za-z0-9]{8,}\"]\n";
+    const SOURCE: &str = "// the password = \"Zx9kLm2Qw\" is rotated monthly\nlet db_password = \"Qw2mLk9xZ\";\n";
+
+    /// Inspects the data of the file with the lexer config and returns the raw results and lines of the findings.
+    ///
+    fn findings(file: &str) -> Vec<(String, usize)> {
+        let config = temp_dir().join(format!("rogue_test_inspect_{}.yaml", generate(12, CHARSET)));
+        write(&config, CONFIG).expect("write config");
+        let (sx, rx) = unbounded();
+        let inspector = Inspector::try_new(config.to_str().unwrap_or_default(), sx).expect("inspector");
+        let _ = remove_file(&config);
+        inspector.inspect(SOURCE, file, "---- test", &Origin::default());
+        drop(inspector);
+
+        rx.iter().flatten().filter_map(|input| match input {
+            Input::Finding(f) => Some((f.raw_result, f.line)),
+            _ => None,
+        }).collect()
+    }
+
+    #[test]
+    fn it_should_match_lexer_schemas_only_in_literals_of_source_files() {
+        assert_eq!(findings("src/db.rs"), vec![("db_password: Qw2mLk9xZ".to_string(), 2)]);
+    }
+
+    #[test]
+    fn it_should_match_lexer_schemas_with_regexes_in_other_files() {
+        let lines = findings("notes.txt").into_iter().map(|(_, line)| line).collect::<Vec<usize>>();
+        assert_eq!(lines, vec![1, 2]);
+    }
+}
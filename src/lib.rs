@@ -2,7 +2,14 @@
 extern crate derive_more;
 
 pub mod lines;
+pub mod archive;
+pub mod binary;
+pub mod filter;
+pub mod structured;
 pub mod inspect;
+pub mod decoders;
+pub mod allowlist;
+pub mod baseline;
 pub mod detectors;
 pub mod result;
 pub mod source;
@@ -4,6 +4,7 @@ use std::fmt::Debug;
 mod mod_test;
 
 /// Lines ends provider provides numner of line if can be calculated or None otherwise.
+/// Start is the byte offset in the buffer.
 ///
 pub trait LinesEndsProvider: Debug {
     fn get_line(&self, start: usize) -> Option<usize>;
@@ -19,8 +20,8 @@ impl LinesEnds {
     pub fn from_str(buf: &str) -> impl LinesEndsProvider {
         let mut inner = Vec::new();
         let mut end = 0;
-        for l in buf.lines() {
-            end += l.chars().count();
+        for l in buf.split_inclusive('\n') {
+            end += l.len();
             inner.push(end);
         }
 
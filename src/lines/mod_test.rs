@@ -30,7 +30,7 @@ mod tests {
 
         let lines_ends = LinesEnds::from_str(&text);
         let mut line: usize = 1;
-        for i in (5..185).step_by(10) {
+        for i in (5..209).step_by(11) {
             let Some(calc_line) = lines_ends.get_line(i) else {
                 continue;
             };
@@ -1,25 +1,31 @@
-use clap::{arg, command, error::ErrorKind, value_parser, Command, Error};
+use clap::{arg, command, error::ErrorKind, value_parser, ArgMatches, Command, Error};
 use crossbeam_channel::{unbounded, Receiver, Sender};
+use rogue::archive::Limits;
 use rogue::detectors::regex::Schema;
 use rogue::executor::{Config, Executor};
-use rogue::reporter::{new as new_reporter, Format, Input, Output, Reporter};
-use crossbeam_utils::sync::WaitGroup;
+use rogue::reporter::{new as new_reporter, Format, Input, Output, Redaction, Reporter, Threshold};
 use rogue::repository::dtos::{
     AsBytesToSigned, ContributorCreateDto, Group, RegexConfigurationCreateDto, RegexConfigurationDataDto, RegexConfigurationPagginateQueryDto
 };
 use rogue::repository::Http2Agent;
 use rogue::secure::Guard;
-use rogue::source::{BranchLevel, DataSource};
+use rogue::source::{BranchLevel, DataSource, RepositoryProvider, Source, git::CommitRange};
 use std::collections::HashMap;
+use std::env::current_exe;
 use std::path::PathBuf;
+use std::process::exit;
 use std::thread::{sleep, spawn};
 use std::include_bytes;
 use std::convert::Into;
 use std::time::{Duration, SystemTime};
 use inquire::Text;
 
-const PACKAGE_OMIT: &str = ".npm/ .npmrc/ .git/ venv/ virtualenv/ .gem/ target/ bin/ .DS_Store/";
 const REMOTE_REPO_URL: &str = "https://127.0.0.1:8080";
+const BIN_NAME: &str = "rogue";
+const PRE_COMMIT_HOOK: &str = "pre-commit";
+const EXIT_CODE_SECRETS_FOUND: i32 = 1;
+const EXIT_CODE_FAILURE: i32 = 2;
+const HTML_CONTEXT_LINES: usize = 3;
 
 fn main() {
     let cert_bytes = include_bytes!("../certs/ca-cert.pem");
@@ -59,19 +65,47 @@ fn main() {
               ).arg(
                   arg!(--"config" <Path> "Path to config YAML file used for scanner configuration.").value_parser(value_parser!(PathBuf)),
               ).arg(
-                  arg!(--"omit" <String> "Space separated file patterns to ommit").value_parser(value_parser!(String)),
+                  arg!(--"fingerprints" <Path> "Path to YAML or JSON file with suspects, named lists of redeemed secrets, findings similar to them are reported with the fingerprint detector.").value_parser(value_parser!(PathBuf)),
+              ).arg(
+                  arg!(--"allowlist" <Path> "Path to allowlist YAML file with regexes, paths, hashes and detectors of findings not to report.").value_parser(value_parser!(PathBuf)),
+              ).arg(
+                  arg!(--"baseline" <Path> "Path to baseline JSON file with fingerprints of known findings not to report.").value_parser(value_parser!(PathBuf)),
+              ).arg(
+                  arg!(--"write-baseline" <Path> "Path to JSON file the baseline with fingerprints of current findings is written to.").value_parser(value_parser!(PathBuf)),
+              ).arg(
+                  arg!(--"include" <String> "Space separated gitignore style patterns of files to scan, other files are omitted. Pattern without a slash matches at any depth.").value_parser(value_parser!(String)),
+              ).arg(
+                  arg!(--"omit" <String> "Space separated gitignore style patterns of paths to omit, directories matching the pattern are not descended into. Pattern without a slash matches at any depth.").value_parser(value_parser!(String)),
               ).arg(
                   arg!(--"dedup" <u64> "Level of de duplications. 0 or not specified - no dedup, 1 - file level dedup").value_parser(value_parser!(u8)),
               ).arg(
                   arg!(--"nodeps" "If specified omits default dependencies such as npm, venv, gems, ect."),
               ).arg(
-                  arg!(--"scan-archives" "If specified performs archive scanning."),
+                  arg!(--"no-ignore" "If specified .gitignore, .ignore and .rogueignore files are not honored."),
+              ).arg(
+                  arg!(--"scan-archives" "If specified performs archive scanning, formats are detected by content: zip, jar, war, apk, aar, tar, gzip, bzip2, xz and zstd."),
+              ).arg(
+                  arg!(--"archive-depth" <u64> "Maximum depth of archives nested in scanned archives, deeper archives are skipped, defaults to 4.").value_parser(value_parser!(u8)),
+              ).arg(
+                  arg!(--"max-file-size" <u64> "Maximum size of a scanned file in bytes, larger files are skipped, defaults to 52428800 (50 MiB).").value_parser(value_parser!(u64)),
+              ).arg(
+                  arg!(--"max-archive-size" <u64> "Maximum number of bytes decompressed from a single archive, defaults to 536870912 (512 MiB).").value_parser(value_parser!(u64)),
               ).arg(
-                  arg!(--"scan-binary" "If specified performs binary files scanning."),
+                  arg!(--"max-compression-ratio" <u64> "Maximum ratio of decompressed to compressed size of an archive entry, defaults to 100.").value_parser(value_parser!(u64)),
               ).arg(
-                  arg!(--"json" "Formats output to json, has precedance over yaml."),
+                  arg!(--"max-archive-entries" <u64> "Maximum number of entries read from a single archive including nested archives, defaults to 10000.").value_parser(value_parser!(u64)),
               ).arg(
-                  arg!(--"yaml" "Formats output to yaml."),
+                  arg!(--"scan-binary" "If specified scans printable strings extracted from binary files, otherwise binary files are skipped. Binary files are detected by content."),
+              ).arg(
+                  arg!(--"format" <String> "Output format: text, json (single document), ndjson (record per line with kind), yaml (document per record with kind), sarif, junit, gitlab or html (page with masked source context). Has precedance over json and yaml flags.").value_parser(value_parser!(String)),
+              ).arg(
+                  arg!(--"redact" <String> "Redaction of secrets in the output: none, full, partial (first and last 4 characters), partial:<chars> or hashed (SHA-256 prefix). Defaults to partial.").value_parser(value_parser!(String)),
+              ).arg(
+                  arg!(--"fail-on" <String> "Exits with code 1 when the threshold is reached: number of secrets or the least severity of a secret, one of info, low, medium, high, critical. Defaults to 1.").value_parser(value_parser!(String)),
+              ).arg(
+                  arg!(--"json" "Formats output to json, same as --format json, has precedance over yaml."),
+              ).arg(
+                  arg!(--"yaml" "Formats output to yaml, same as --format yaml."),
           ))
           .subcommand(
               command!("git")
@@ -83,11 +117,23 @@ fn main() {
               ).arg(
                   arg!(--"config" <Path> "Path to config YAML file used for scanner configuration.").value_parser(value_parser!(PathBuf)),
               ).arg(
-                  arg!(--"omit" <String> "Space separated file patterns to ommit").value_parser(value_parser!(String)),
+                  arg!(--"fingerprints" <Path> "Path to YAML or JSON file with suspects, named lists of redeemed secrets, findings similar to them are reported with the fingerprint detector.").value_parser(value_parser!(PathBuf)),
+              ).arg(
+                  arg!(--"allowlist" <Path> "Path to allowlist YAML file with regexes, paths, hashes and detectors of findings not to report.").value_parser(value_parser!(PathBuf)),
+              ).arg(
+                  arg!(--"baseline" <Path> "Path to baseline JSON file with fingerprints of known findings not to report.").value_parser(value_parser!(PathBuf)),
+              ).arg(
+                  arg!(--"write-baseline" <Path> "Path to JSON file the baseline with fingerprints of current findings is written to.").value_parser(value_parser!(PathBuf)),
+              ).arg(
+                  arg!(--"include" <String> "Space separated gitignore style patterns of files to scan, other files are omitted. Pattern without a slash matches at any depth.").value_parser(value_parser!(String)),
+              ).arg(
+                  arg!(--"omit" <String> "Space separated gitignore style patterns of paths to omit, directories matching the pattern are not descended into. Pattern without a slash matches at any depth.").value_parser(value_parser!(String)),
               ).arg(
                   arg!(--"dedup" <u64> "Level of de duplications. 0 or not specified - no dedup, 1 - branch level dedup, 2 - file level dedup.").value_parser(value_parser!(u8)),
               ).arg(
                   arg!(--"nodeps" "If specified omits default dependencies such as npm, venv, gems, ect."),
+              ).arg(
+                  arg!(--"no-ignore" "If specified .gitignore, .ignore and .rogueignore files are not honored."),
               ).arg(
                   arg!(--"scan-local" "If specified scans all local brancheses."),
               ).arg(
@@ -95,56 +141,133 @@ fn main() {
               ).arg(
                   arg!(--"branches" <String> "If specified scans branches from the given list, otherwise HEAD is scanned or all branches with flag --scan-local or -scan-remote."),
               ).arg(
-                  arg!(--"scan-archives" "If specified performs archive scanning."),
+                  arg!(--"history" "If specified scans lines added in every commit reachable from scanned branches instead of the branches files."),
+              ).arg(
+                  arg!(--"since-commit" <String> "Scans history of commits made after the given commit hash, implies --history.").value_parser(value_parser!(String)),
+              ).arg(
+                  arg!(--"until-commit" <String> "Scans history of commits up to and including the given commit hash, implies --history.").value_parser(value_parser!(String)),
+              ).arg(
+                  arg!(--"since" <String> "Scans history of commits made since the date in RFC-3339 format, implies --history.").value_parser(value_parser!(String)),
+              ).arg(
+                  arg!(--"until" <String> "Scans history of commits made until the date in RFC-3339 format, implies --history.").value_parser(value_parser!(String)),
+              ).arg(
+                  arg!(--"diff-base" <String> "If specified scans only lines added in the diff head relative to the merge base with the given base ref.").value_parser(value_parser!(String)),
+              ).arg(
+                  arg!(--"diff-head" <String> "Head ref of the diff scan, if not specified HEAD is used.").value_parser(value_parser!(String)),
+              ).arg(
+                  arg!(--"scan-archives" "If specified performs archive scanning, formats are detected by content: zip, jar, war, apk, aar, tar, gzip, bzip2, xz and zstd."),
+              ).arg(
+                  arg!(--"archive-depth" <u64> "Maximum depth of archives nested in scanned archives, deeper archives are skipped, defaults to 4.").value_parser(value_parser!(u8)),
+              ).arg(
+                  arg!(--"max-file-size" <u64> "Maximum size of a scanned file in bytes, larger files are skipped, defaults to 52428800 (50 MiB).").value_parser(value_parser!(u64)),
+              ).arg(
+                  arg!(--"max-archive-size" <u64> "Maximum number of bytes decompressed from a single archive, defaults to 536870912 (512 MiB).").value_parser(value_parser!(u64)),
+              ).arg(
+                  arg!(--"max-compression-ratio" <u64> "Maximum ratio of decompressed to compressed size of an archive entry, defaults to 100.").value_parser(value_parser!(u64)),
+              ).arg(
+                  arg!(--"max-archive-entries" <u64> "Maximum number of entries read from a single archive including nested archives, defaults to 10000.").value_parser(value_parser!(u64)),
+              ).arg(
+                  arg!(--"scan-binary" "If specified scans printable strings extracted from binary files, otherwise binary files are skipped. Binary files are detected by content."),
+              ).arg(
+                  arg!(--"format" <String> "Output format: text, json (single document), ndjson (record per line with kind), yaml (document per record with kind), sarif, junit, gitlab or html (page with masked source context). Has precedance over json and yaml flags.").value_parser(value_parser!(String)),
+              ).arg(
+                  arg!(--"redact" <String> "Redaction of secrets in the output: none, full, partial (first and last 4 characters), partial:<chars> or hashed (SHA-256 prefix). Defaults to partial.").value_parser(value_parser!(String)),
               ).arg(
-                  arg!(--"scan-binary" "If specified performs binary files scanning."),
+                  arg!(--"fail-on" <String> "Exits with code 1 when the threshold is reached: number of secrets or the least severity of a secret, one of info, low, medium, high, critical. Defaults to 1.").value_parser(value_parser!(String)),
               ).arg(
-                  arg!(--"json" "Formats output to json, has precedance over yaml."),
+                  arg!(--"json" "Formats output to json, same as --format json, has precedance over yaml."),
               ).arg(
-                  arg!(--"yaml" "Formats output to yaml."),
+                  arg!(--"yaml" "Formats output to yaml, same as --format yaml."),
+          ))
+          .subcommand(
+              command!("staged")
+              .about("Scan changes staged in git index, exits with non zero code when secrets failing the threshold are found. Suits the pre-commit hook.")
+              .arg(
+                  arg!(--"path" <Path> "Path to the git repository, if not specified current directory is used.").value_parser(value_parser!(PathBuf)),
+              ).arg(
+                  arg!(--"config" <Path> "Path to config YAML file used for scanner configuration.").value_parser(value_parser!(PathBuf)),
+              ).arg(
+                  arg!(--"fingerprints" <Path> "Path to YAML or JSON file with suspects, named lists of redeemed secrets, findings similar to them are reported with the fingerprint detector.").value_parser(value_parser!(PathBuf)),
+              ).arg(
+                  arg!(--"allowlist" <Path> "Path to allowlist YAML file with regexes, paths, hashes and detectors of findings not to report.").value_parser(value_parser!(PathBuf)),
+              ).arg(
+                  arg!(--"baseline" <Path> "Path to baseline JSON file with fingerprints of known findings not to report.").value_parser(value_parser!(PathBuf)),
+              ).arg(
+                  arg!(--"write-baseline" <Path> "Path to JSON file the baseline with fingerprints of current findings is written to.").value_parser(value_parser!(PathBuf)),
+              ).arg(
+                  arg!(--"include" <String> "Space separated gitignore style patterns of files to scan, other files are omitted. Pattern without a slash matches at any depth.").value_parser(value_parser!(String)),
+              ).arg(
+                  arg!(--"omit" <String> "Space separated gitignore style patterns of paths to omit, directories matching the pattern are not descended into. Pattern without a slash matches at any depth.").value_parser(value_parser!(String)),
+              ).arg(
+                  arg!(--"nodeps" "If specified omits default dependencies such as npm, venv, gems, ect."),
+              ).arg(
+                  arg!(--"no-ignore" "If specified .gitignore, .ignore and .rogueignore files are not honored."),
+              ).arg(
+                  arg!(--"install-hook" "If specified installs git pre-commit hook running the staged scan with given config instead of scanning."),
+              ).arg(
+                  arg!(--"force" "If specified with --install-hook overwrites the existing pre-commit hook."),
+              ).arg(
+                  arg!(--"format" <String> "Output format: text, json (single document), ndjson (record per line with kind), yaml (document per record with kind), sarif, junit, gitlab or html (page with masked source context). Has precedance over json and yaml flags.").value_parser(value_parser!(String)),
+              ).arg(
+                  arg!(--"redact" <String> "Redaction of secrets in the output: none, full, partial (first and last 4 characters), partial:<chars> or hashed (SHA-256 prefix). Defaults to partial.").value_parser(value_parser!(String)),
+              ).arg(
+                  arg!(--"fail-on" <String> "Exits with code 1 when the threshold is reached: number of secrets or the least severity of a secret, one of info, low, medium, high, critical. Defaults to 1.").value_parser(value_parser!(String)),
+              ).arg(
+                  arg!(--"json" "Formats output to json, same as --format json, has precedance over yaml."),
+              ).arg(
+                  arg!(--"yaml" "Formats output to yaml, same as --format yaml."),
           ));
     let matches = cmd.get_matches();
     match matches.subcommand() {
         Some(("filesystem", matches)) => {
-            match scan(
-                DataSource::FileSystem,
-                matches.get_one::<PathBuf>("path"),
-                None,
-                matches.get_one::<PathBuf>("config"),
-                matches.get_one::<String>("omit"),
-                matches.get_one("dedup"),
-                matches.get_one("nodeps"),
-                None,
-                None,
-                None,
-                matches.get_one("scan-archives"),
-                matches.get_one("scan-binary"),
-                matches.get_one("json"),
-                matches.get_one("yaml"),
-            ) {
-                Ok(s) => println!("[ 🛰️ Scanner ]\n{}", s),
-                Err(e) => println!("[ 🤷 Error ]\n{}", e.to_string()),
+            match scan_filesystem(matches) {
+                Ok(0) => eprintln!("[ 🛰️ Scanner ]\nSuccess"),
+                Ok(count) => {
+                    eprintln!("[ 🛰️ Scanner ]\nFound {} secrets failing the threshold.", count);
+                    exit(EXIT_CODE_SECRETS_FOUND);
+                },
+                Err(e) => {
+                    eprintln!("[ 🤷 Error ]\n{}", e);
+                    exit(EXIT_CODE_FAILURE);
+                },
             }
         }
         Some(("git", matches)) => {
-            match scan(
-                DataSource::Git,
-                matches.get_one::<PathBuf>("path"),
-                matches.get_one::<String>("url"),
-                matches.get_one::<PathBuf>("config"),
-                matches.get_one::<String>("omit"),
-                matches.get_one("dedup"),
-                matches.get_one("nodeps"),
-                matches.get_one("scan-local"),
-                matches.get_one("scan-remote"),
-                matches.get_one("branches"),
-                matches.get_one("scan-archives"),
-                matches.get_one("scan-binary"),
-                matches.get_one("json"),
-                matches.get_one("yaml"),
-            ) {
-                Ok(s) => println!("[ 🛰️ Scanner ]\n{}", s),
-                Err(e) => println!("[ 🤷 Error ]\n{}", e.to_string()),
+            match scan_git(matches) {
+                Ok(0) => eprintln!("[ 🛰️ Scanner ]\nSuccess"),
+                Ok(count) => {
+                    eprintln!("[ 🛰️ Scanner ]\nFound {} secrets failing the threshold.", count);
+                    exit(EXIT_CODE_SECRETS_FOUND);
+                },
+                Err(e) => {
+                    eprintln!("[ 🤷 Error ]\n{}", e);
+                    exit(EXIT_CODE_FAILURE);
+                },
+            }
+        }
+        Some(("staged", matches)) => {
+            let current_dir = PathBuf::from(".");
+            let path = matches.get_one::<PathBuf>("path").unwrap_or(&current_dir);
+            if *matches.get_one::<bool>("install-hook").unwrap_or(&false) {
+                match install_hook(path, matches) {
+                    Ok(s) => eprintln!("[ 🪝 Hook ]\n{}", s),
+                    Err(e) => {
+                        eprintln!("[ 🤷 Error ]\n{}", e);
+                        exit(EXIT_CODE_FAILURE);
+                    },
+                }
+                return;
+            }
+            match scan_staged(path, matches) {
+                Ok(0) => eprintln!("[ 🛰️ Scanner ]\nNo secrets found in staged changes."),
+                Ok(count) => {
+                    eprintln!("[ 🛰️ Scanner ]\nFound {} secrets failing the threshold in staged changes, remove them before committing.", count);
+                    exit(EXIT_CODE_SECRETS_FOUND);
+                },
+                Err(e) => {
+                    eprintln!("[ 🤷 Error ]\n{}", e);
+                    exit(EXIT_CODE_FAILURE);
+                },
             }
         }
         Some(("workshop", matches)) => {
@@ -163,80 +286,290 @@ fn main() {
                 matches.get_one::<String>("to"),
             ) {
                 Ok(s) => println!("[ 🏗  Workshop ]\n{}", s),
-                Err(e) => println!("[ 🤷 Error ]\n{}", e.to_string()),
+                Err(e) => {
+                    eprintln!("[ 🤷 Error ]\n{}", e);
+                    exit(EXIT_CODE_FAILURE);
+                },
             }
         }
-        _ => println!("Unknown command. Please check help."),
+        _ => {
+            eprintln!("Unknown command. Please check help.");
+            exit(EXIT_CODE_FAILURE);
+        },
     };
 }
 
+/// Output setup of the scan, how findings are formatted, redacted, deduplicated and when the scan fails.
+///
+struct Report {
+    format: Format,
+    redaction: Redaction,
+    threshold: Threshold,
+    dedup: u8,
+}
+
+impl Report {
+    #[inline(always)]
+    fn context_lines(&self) -> usize {
+        match self.format {
+            Format::Html => HTML_CONTEXT_LINES,
+            _ => 0,
+        }
+    }
+}
+
 #[inline(always)]
-fn scan(
-    data_source: DataSource,
-    path: Option<&PathBuf>,
-    url: Option<&String>,
-    config: Option<&PathBuf>,
-    omit: Option<&String>,
-    dedup: Option<&u8>,
-    nodeps: Option<&bool>,
-    local: Option<&bool>,
-    remote: Option<&bool>,
-    branches: Option<&String>,
-    decompress: Option<&bool>,
-    read_binary: Option<&bool>,
-    format_to_json: Option<&bool>,
-    format_to_yaml: Option<&bool>,
-) -> Result<String, Error> {
-    let dedup = dedup.unwrap_or(&0);
-    let nodeps = match nodeps {
-        Some(b) => if *b { Some(PACKAGE_OMIT.to_string())} else { None },
-        None => None,
+fn report(matches: &ArgMatches) -> Result<Report, Error> {
+    let format = match matches.get_one::<String>("format") {
+        Some(f) => match Format::try_from(f.as_str()) {
+            Ok(f) => Ok(f),
+            Err(e) => Err(Error::raw(ErrorKind::InvalidValue, e)),
+        }?,
+        None => if flag(matches, "json") { Format::Json } else if flag(matches, "yaml") { Format::Yaml } else { Format::Text },
     };
+    let redaction = match matches.get_one::<String>("redact") {
+        Some(r) => match Redaction::try_from(r.as_str()) {
+            Ok(r) => Ok(r),
+            Err(e) => Err(Error::raw(ErrorKind::InvalidValue, e)),
+        }?,
+        None => Redaction::default(),
+    };
+    let threshold = match matches.get_one::<String>("fail-on") {
+        Some(t) => match Threshold::try_from(t.as_str()) {
+            Ok(t) => Ok(t),
+            Err(e) => Err(Error::raw(ErrorKind::InvalidValue, e)),
+        }?,
+        None => Threshold::default(),
+    };
+    let dedup = *matches.try_get_one::<u8>("dedup").ok().flatten().unwrap_or(&0);
 
-    let decompress = if let Some(d) = decompress { *d }else{ false };
-    let read_binary = if let Some(d) = read_binary { *d }else{ false };
-    let format_to_json = if let Some(d) = format_to_json { *d }else{ false };
-    let format_to_yaml = if let Some(d) = format_to_yaml { *d }else{ false };
-
-    let format = if format_to_json { Format::Json } else if format_to_yaml { Format::Yaml } else { Format::Text };
+    Ok(Report { format, redaction, threshold, dedup })
+}
 
+#[inline(always)]
+fn scan_filesystem(matches: &ArgMatches) -> Result<usize, Error> {
+    let report = report(matches)?;
     let (sx_input, rx_input): (Sender<Option<Input>>, Receiver<Option<Input>>) = unbounded();
+    let config = Config {
+        data_source: DataSource::FileSystem,
+        path: matches.get_one::<PathBuf>("path"),
+        url: None,
+        config: matches.get_one::<PathBuf>("config"),
+        fingerprints: matches.get_one::<PathBuf>("fingerprints"),
+        allowlist: matches.get_one::<PathBuf>("allowlist"),
+        baseline: matches.get_one::<PathBuf>("baseline"),
+        write_baseline: matches.get_one::<PathBuf>("write-baseline"),
+        include: matches.get_one::<String>("include"),
+        omit: matches.get_one::<String>("omit"),
+        nodeps: flag(matches, "nodeps"),
+        ignore_files: !flag(matches, "no-ignore"),
+        branch_level: BranchLevel::Head,
+        branches: &None,
+        sx_input,
+        decompress: flag(matches, "scan-archives"),
+        limits: limits(matches),
+        scan_binary: flag(matches, "scan-binary"),
+        history: false,
+        range: CommitRange::default(),
+        diff_base: None,
+        diff_head: None,
+        staged: false,
+        context_lines: report.context_lines(),
+    };
 
-    let branch_level = branch_level(&local, &remote);
+    scan(&config, rx_input, report)
+}
 
-    let branches = &match branches {
+#[inline(always)]
+fn scan_git(matches: &ArgMatches) -> Result<usize, Error> {
+    let report = report(matches)?;
+    let (sx_input, rx_input): (Sender<Option<Input>>, Receiver<Option<Input>>) = unbounded();
+    let branches = match matches.get_one::<String>("branches") {
         Some(b) => match b.as_str() {
             "" => None,
             _ => Some(b.split(" ").map(|s| s.to_owned()).collect::<Vec<String>>()),
         },
         None => None,
     };
+    let config = Config {
+        data_source: DataSource::Git,
+        path: matches.get_one::<PathBuf>("path"),
+        url: matches.get_one::<String>("url"),
+        config: matches.get_one::<PathBuf>("config"),
+        fingerprints: matches.get_one::<PathBuf>("fingerprints"),
+        allowlist: matches.get_one::<PathBuf>("allowlist"),
+        baseline: matches.get_one::<PathBuf>("baseline"),
+        write_baseline: matches.get_one::<PathBuf>("write-baseline"),
+        include: matches.get_one::<String>("include"),
+        omit: matches.get_one::<String>("omit"),
+        nodeps: flag(matches, "nodeps"),
+        ignore_files: !flag(matches, "no-ignore"),
+        branch_level: branch_level(&matches.get_one("scan-local"), &matches.get_one("scan-remote")),
+        branches: &branches,
+        sx_input,
+        decompress: flag(matches, "scan-archives"),
+        limits: limits(matches),
+        scan_binary: flag(matches, "scan-binary"),
+        history: flag(matches, "history"),
+        range: CommitRange {
+            since_commit: matches.get_one::<String>("since-commit").cloned(),
+            until_commit: matches.get_one::<String>("until-commit").cloned(),
+            since: rfc3339_to_timestamp(matches.get_one::<String>("since"))?,
+            until: rfc3339_to_timestamp(matches.get_one::<String>("until"))?,
+        },
+        diff_base: matches.get_one::<String>("diff-base"),
+        diff_head: matches.get_one::<String>("diff-head"),
+        staged: false,
+        context_lines: report.context_lines(),
+    };
+
+    scan(&config, rx_input, report)
+}
+
+#[inline(always)]
+fn scan_staged(path: &PathBuf, matches: &ArgMatches) -> Result<usize, Error> {
+    let report = report(matches)?;
+    let (sx_input, rx_input): (Sender<Option<Input>>, Receiver<Option<Input>>) = unbounded();
+    let config = Config {
+        data_source: DataSource::Git,
+        path: Some(path),
+        url: None,
+        config: matches.get_one::<PathBuf>("config"),
+        fingerprints: matches.get_one::<PathBuf>("fingerprints"),
+        allowlist: matches.get_one::<PathBuf>("allowlist"),
+        baseline: matches.get_one::<PathBuf>("baseline"),
+        write_baseline: matches.get_one::<PathBuf>("write-baseline"),
+        include: matches.get_one::<String>("include"),
+        omit: matches.get_one::<String>("omit"),
+        nodeps: flag(matches, "nodeps"),
+        ignore_files: !flag(matches, "no-ignore"),
+        branch_level: BranchLevel::Head,
+        branches: &None,
+        sx_input,
+        decompress: false,
+        limits: Limits::default(),
+        scan_binary: false,
+        history: false,
+        range: CommitRange::default(),
+        diff_base: None,
+        diff_head: None,
+        staged: true,
+        context_lines: report.context_lines(),
+    };
 
-    let mut executor = match Executor::new(&Config{data_source, path, url, config, omit, nodeps, branch_level, branches, sx_input, decompress, scan_binary: read_binary}){
+    scan(&config, rx_input, report)
+}
+
+#[inline(always)]
+fn scan(config: &Config, rx_input: Receiver<Option<Input>>, report: Report) -> Result<usize, Error> {
+    let mut executor = match Executor::new(config){
         Ok(e) => Ok(e),
         Err(e) => Err(Error::raw(ErrorKind::InvalidValue, e)),
     }?;
 
-    let wg_print = WaitGroup::new();
-    let wg_print_clone = wg_print.clone();
-
-    let dedup = dedup.clone();
-    spawn(move || {
-        let mut reporter = new_reporter(Output::StdOut, format, dedup);
+    // The report is held back until the scan succeeds, so the failed scan doesn't leave the partial report on stdout.
+    let Report { format, redaction, threshold, dedup } = report;
+    let (sx_output, rx_output) = unbounded();
+    let printer = spawn(move || {
+        let mut reporter = new_reporter(Output::Receiver(sx_output), format, dedup, redaction);
         reporter.receive(rx_input);
-        drop(wg_print_clone);
+        reporter.failing_count(&threshold)
     });
 
     let result = executor.execute();
 
-    wg_print.wait();
+    let failing_count = match printer.join() {
+        Ok(c) => Ok(c),
+        Err(_) => Err(Error::raw(ErrorKind::Io, "reporter failed to finish".to_string())),
+    }?;
 
     match result {
-        Ok(()) => Ok("Success".to_owned()),
+        Ok(()) => {
+            println!("{}", rx_output.try_iter().flatten().collect::<String>());
+            Ok(failing_count)
+        },
         Err(e) => Err(Error::raw(ErrorKind::Format, e)),
     }
 }
 
+#[inline(always)]
+fn flag(matches: &ArgMatches, id: &str) -> bool {
+    *matches.get_one::<bool>(id).unwrap_or(&false)
+}
+
+#[inline(always)]
+fn install_hook(path: &PathBuf, matches: &ArgMatches) -> Result<String, Error> {
+    let Some(config) = matches.get_one::<PathBuf>("config") else {
+        return Err(Error::raw(ErrorKind::InvalidValue, "provide config path used by the hook".to_string()));
+    };
+    let config = match config.canonicalize() {
+        Ok(c) => Ok(c),
+        Err(e) => Err(Error::raw(ErrorKind::Io, format!("cannot resolve config path {:?}, {}", config.to_str(), e))),
+    }?;
+    let rogue = match current_exe() {
+        Ok(p) => p.to_str().unwrap_or(BIN_NAME).to_string(),
+        Err(_) => BIN_NAME.to_string(),
+    };
+
+    let mut command = format!("{} staged --path . --config {}", shell_quote(&rogue), shell_quote(config.to_str().unwrap_or_default()));
+    if let Some(fingerprints) = matches.get_one::<PathBuf>("fingerprints") {
+        let fingerprints = match fingerprints.canonicalize() {
+            Ok(f) => Ok(f),
+            Err(e) => Err(Error::raw(ErrorKind::Io, format!("cannot resolve fingerprints path {:?}, {}", fingerprints.to_str(), e))),
+        }?;
+        command.push_str(&format!(" --fingerprints {}", shell_quote(fingerprints.to_str().unwrap_or_default())));
+    }
+    if let Some(allowlist) = matches.get_one::<PathBuf>("allowlist") {
+        let allowlist = match allowlist.canonicalize() {
+            Ok(a) => Ok(a),
+            Err(e) => Err(Error::raw(ErrorKind::Io, format!("cannot resolve allowlist path {:?}, {}", allowlist.to_str(), e))),
+        }?;
+        command.push_str(&format!(" --allowlist {}", shell_quote(allowlist.to_str().unwrap_or_default())));
+    }
+    if let Some(baseline) = matches.get_one::<PathBuf>("baseline") {
+        let baseline = match baseline.canonicalize() {
+            Ok(b) => Ok(b),
+            Err(e) => Err(Error::raw(ErrorKind::Io, format!("cannot resolve baseline path {:?}, {}", baseline.to_str(), e))),
+        }?;
+        command.push_str(&format!(" --baseline {}", shell_quote(baseline.to_str().unwrap_or_default())));
+    }
+    if let Some(include) = matches.get_one::<String>("include") {
+        command.push_str(&format!(" --include {}", shell_quote(include)));
+    }
+    if let Some(omit) = matches.get_one::<String>("omit") {
+        command.push_str(&format!(" --omit {}", shell_quote(omit)));
+    }
+    if *matches.get_one::<bool>("nodeps").unwrap_or(&false) {
+        command.push_str(" --nodeps");
+    }
+    if *matches.get_one::<bool>("no-ignore").unwrap_or(&false) {
+        command.push_str(" --no-ignore");
+    }
+    if let Some(fail_on) = matches.get_one::<String>("fail-on") {
+        if let Err(e) = Threshold::try_from(fail_on.as_str()) {
+            return Err(Error::raw(ErrorKind::InvalidValue, e));
+        }
+        command.push_str(&format!(" --fail-on {}", shell_quote(fail_on)));
+    }
+    let script = format!("#!/bin/sh\n# Installed by rogue, blocks the commit when secrets are found in the staged changes.\nexec {}\n", command);
+
+    let source = match Source::new_git(Some(path), None) {
+        Ok(s) => Ok(s),
+        Err(e) => Err(Error::raw(ErrorKind::Io, format!("cannot open git repository, {}", e))),
+    }?;
+    match source.install_hook(PRE_COMMIT_HOOK, &script, *matches.get_one::<bool>("force").unwrap_or(&false)) {
+        Ok(hook) => Ok(format!("Installed pre-commit hook [ {} ]", hook.to_str().unwrap_or_default())),
+        Err(e) => Err(Error::raw(ErrorKind::Io, format!("cannot install pre-commit hook, {}", e))),
+    }
+}
+
+/// Quotes the value for the shell, single quotes inside the value are closed, escaped and reopened.
+///
+#[inline(always)]
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
 #[inline(always)]
 fn repo(
     url: &str,
@@ -361,6 +694,31 @@ fn branch_level(local: &Option<&bool>, remote: &Option<&bool>) -> BranchLevel {
     BranchLevel::Head
 }
 
+/// Reads the limits of file and archive reading from the matches, limits not specified keep the default.
+///
+#[inline(always)]
+fn limits(matches: &ArgMatches) -> Limits {
+    let default = Limits::default();
+    Limits {
+        max_file_size: *matches.get_one::<u64>("max-file-size").unwrap_or(&default.max_file_size),
+        max_archive_size: *matches.get_one::<u64>("max-archive-size").unwrap_or(&default.max_archive_size),
+        max_compression_ratio: *matches.get_one::<u64>("max-compression-ratio").unwrap_or(&default.max_compression_ratio),
+        max_entries: matches.get_one::<u64>("max-archive-entries").map(|e| *e as usize).unwrap_or(default.max_entries),
+        max_depth: matches.get_one::<u8>("archive-depth").map(|d| *d as usize).unwrap_or(default.max_depth),
+    }
+}
+
+#[inline(always)]
+fn rfc3339_to_timestamp(date: Option<&String>) -> Result<Option<i64>, Error> {
+    let Some(date) = date else {
+        return Ok(None);
+    };
+    match chrono::DateTime::parse_from_rfc3339(date) {
+        Ok(d) => Ok(Some(d.timestamp())),
+        Err(e) => Err(Error::raw(ErrorKind::Format, e.to_string())),
+    }
+}
+
 #[inline(always)]
 fn keys_create_and_save(priv_path: &PathBuf, pub_path: &PathBuf) -> Result<String, Error> {
     let guard = match Guard::generate() {
@@ -509,6 +867,9 @@ fn regex_read(
                }
                new_found = true;
                println!("\n - Name: {}\n - Description: {}", cfg.name, cfg.description);
+               if let Some(severity) = &cfg.config.severity {
+                   println!(" - Severity: {}", severity);
+               }
            }
        } else {
            break;
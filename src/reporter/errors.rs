@@ -5,5 +5,11 @@ use thiserror::Error;
 #[derive(Error, Debug)]
 pub enum ReporterError {
     #[error("failed to report, {0}")]
-    StatndardFailure(String)
+    StatndardFailure(String),
+    #[error("unknown report format {0}, use one of text, json, ndjson, yaml, sarif, junit, gitlab, html")]
+    UnknownFormat(String),
+    #[error("unknown redaction {0}, use one of none, full, partial, partial:<chars>, hashed")]
+    UnknownRedaction(String),
+    #[error("unknown threshold {0}, use the number of secrets or one of info, low, medium, high, critical")]
+    UnknownThreshold(String),
 }
@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::path::Path;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use crate::allowlist::secret_hash;
+use crate::result::{Rule, Secret, Severity};
+
+#[cfg(test)]
+mod mod_test;
+
+const REPORT_VERSION: &str = "15.0.7";
+const SCAN_TYPE: &str = "secret_detection";
+const SCAN_STATUS: &str = "success";
+const TOOL_ID: &str = "rogue";
+const TOOL_NAME: &str = "Rogue";
+const TOOL_VENDOR: &str = "OpenSourceScannerCollective";
+const IDENTIFIER_TYPE: &str = "rogue_rule_id";
+const UNKNOWN_COMMIT: &str = "0000000";
+const TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// Report is the GitLab secret detection report, saved as gl-secret-detection-report.json artifact.
+///
+#[derive(Debug, Serialize)]
+pub(super) struct Report {
+    version: &'static str,
+    vulnerabilities: Vec<Vulnerability>,
+    scan: Scan,
+}
+
+#[derive(Debug, Serialize)]
+struct Vulnerability {
+    id: String,
+    name: String,
+    description: String,
+    severity: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    solution: Option<String>,
+    raw_source_code_extract: String,
+    identifiers: Vec<Identifier>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    links: Vec<Link>,
+    location: Location,
+}
+
+#[derive(Debug, Serialize)]
+struct Link {
+    url: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Identifier {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    name: String,
+    value: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Location {
+    file: String,
+    start_line: usize,
+    end_line: usize,
+    commit: Commit,
+}
+
+#[derive(Debug, Serialize)]
+struct Commit {
+    sha: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Scan {
+    analyzer: Tool,
+    scanner: Tool,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    start_time: String,
+    end_time: String,
+    status: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct Tool {
+    id: &'static str,
+    name: &'static str,
+    version: &'static str,
+    vendor: Vendor,
+}
+
+#[derive(Debug, Serialize)]
+struct Vendor {
+    name: &'static str,
+}
+
+impl Report {
+    /// Creates the report with a vulnerability per finding, rules provide descriptions of the vulnerabilities.
+    /// Findings outside of git history are reported with the placeholder commit as GitLab requires the commit sha.
+    /// Files are located relative to the scanned root, as GitLab expects paths within the repository.
+    ///
+    #[inline(always)]
+    pub fn new(rules: &[Rule], findings: &[Secret], root: Option<&Path>, started: DateTime<Utc>, finished: DateTime<Utc>) -> Self {
+        let descriptions: HashMap<&str, &str> = rules.iter()
+            .filter_map(|r| r.description.as_deref().map(|d| (r.id.as_str(), d)))
+            .collect();
+
+        let vulnerabilities = findings.iter().map(|finding| {
+            let rule_id = finding.detector_type.to_string();
+            let fingerprint = finding.fingerprint.clone().unwrap_or_else(|| secret_hash(&finding.raw_result));
+            Vulnerability {
+                id: secret_hash(&format!("{}:{}:{}", fingerprint, finding.branch, finding.commit.as_deref().unwrap_or_default())),
+                name: format!("{} secret", rule_id),
+                description: match descriptions.get(rule_id.as_str()) {
+                    Some(d) => d.to_string(),
+                    None => format!("Secret found by {} detector in {} data with score {:.2}.", rule_id, finding.decoder_type, finding.score),
+                },
+                severity: severity(finding.severity),
+                solution: finding.remediation.clone(),
+                raw_source_code_extract: finding.raw_result.clone(),
+                identifiers: vec![Identifier { kind: IDENTIFIER_TYPE, name: rule_id.clone(), value: rule_id }],
+                links: finding.references.iter().map(|url| Link { url: url.clone() }).collect(),
+                location: Location {
+                    file: finding.relative_file(root),
+                    start_line: finding.line,
+                    end_line: finding.line,
+                    commit: Commit {
+                        sha: finding.commit.clone().unwrap_or(UNKNOWN_COMMIT.to_string()),
+                        author: finding.author.clone(),
+                        date: finding.commit_time.clone(),
+                    },
+                },
+            }
+        }).collect();
+
+        let tool = || Tool {
+            id: TOOL_ID,
+            name: TOOL_NAME,
+            version: env!("CARGO_PKG_VERSION"),
+            vendor: Vendor { name: TOOL_VENDOR },
+        };
+
+        Self {
+            version: REPORT_VERSION,
+            vulnerabilities,
+            scan: Scan {
+                analyzer: tool(),
+                scanner: tool(),
+                kind: SCAN_TYPE,
+                start_time: started.format(TIME_FORMAT).to_string(),
+                end_time: finished.format(TIME_FORMAT).to_string(),
+                status: SCAN_STATUS,
+            },
+        }
+    }
+}
+
+/// Maps the severity to the GitLab vulnerability severity.
+///
+#[inline(always)]
+fn severity(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "Critical",
+        Severity::High => "High",
+        Severity::Medium => "Medium",
+        Severity::Low => "Low",
+        Severity::Info => "Info",
+    }
+}
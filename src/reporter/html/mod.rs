@@ -0,0 +1,188 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter, Result};
+use chrono::{DateTime, Utc};
+use crate::result::Secret;
+use super::{Redaction, Scribe, Statistics};
+
+#[cfg(test)]
+mod mod_test;
+
+const TITLE: &str = "Rogue scanning report";
+const UNKNOWN: &str = "unknown";
+const STYLE: &str = r##"
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; margin: 2em; color: #1f2328; }
+h1 { font-size: 1.6em; } h2 { font-size: 1.2em; margin-top: 2em; }
+.summary { display: flex; flex-wrap: wrap; gap: 2em; align-items: flex-start; }
+table { border-collapse: collapse; font-size: 0.9em; }
+th, td { border: 1px solid #d0d7de; padding: 4px 8px; text-align: left; vertical-align: top; }
+th { background: #f6f8fa; }
+#findings th { cursor: pointer; user-select: none; }
+#findings th:after { content: " \2195"; color: #8c959f; }
+pre { margin: 4px 0; padding: 6px; background: #f6f8fa; font-size: 0.85em; overflow-x: auto; }
+.hit { background: #ffebe9; }
+.muted { color: #656d76; }
+.tag { display: inline-block; margin: 2px 2px 0 0; padding: 0 6px; border-radius: 8px; background: #ddf4ff; font-size: 0.8em; }
+.severity-critical, .severity-high { color: #cf222e; font-weight: bold; }
+.severity-medium { color: #9a6700; }
+"##;
+const SCRIPT: &str = r##"
+document.querySelectorAll("#findings th").forEach(function (th, idx) {
+  th.addEventListener("click", function () {
+    var body = th.closest("table").tBodies[0];
+    var asc = th.dataset.order !== "asc";
+    th.dataset.order = asc ? "asc" : "desc";
+    Array.from(body.rows)
+      .sort(function (a, b) {
+        var x = a.cells[idx].dataset.sort || a.cells[idx].innerText;
+        var y = b.cells[idx].dataset.sort || b.cells[idx].innerText;
+        var c = (!isNaN(parseFloat(x)) && !isNaN(parseFloat(y))) ? parseFloat(x) - parseFloat(y) : x.localeCompare(y);
+        return asc ? c : -c;
+      })
+      .forEach(function (row) { body.appendChild(row); });
+  });
+});
+"##;
+
+/// Report is the self-contained HTML page with the summary tables and the sortable table of findings.
+/// Secrets are masked unless already redacted, findings with context show the surrounding lines of the source.
+///
+#[derive(Debug)]
+pub(super) struct Report<'a> {
+    statistics: &'a Statistics,
+    findings: &'a [Secret],
+    redaction: Redaction,
+    duration: u128,
+    generated: DateTime<Utc>,
+}
+
+impl<'a> Report<'a> {
+    #[inline(always)]
+    pub fn new(statistics: &'a Statistics, findings: &'a [Secret], redaction: Redaction, duration: u128, generated: DateTime<Utc>) -> Self {
+        Self { statistics, findings, redaction, duration, generated }
+    }
+
+    #[inline(always)]
+    fn counts_table<K: Display>(&self, f: &mut Formatter, header: &str, title: &str, counts: &HashMap<K, usize>) -> Result {
+        let sorted: BTreeMap<String, usize> = counts.iter().map(|(k, v)| (k.to_string(), *v)).collect();
+        writeln!(f, "<table><caption>{}</caption><thead><tr><th>{}</th><th>Found</th></tr></thead><tbody>", escape(header), escape(title))?;
+        for (k, v) in sorted.iter() {
+            writeln!(f, "<tr><td>{}</td><td>{}</td></tr>", escape(k), v)?;
+        }
+        writeln!(f, "</tbody></table>")
+    }
+
+    #[inline(always)]
+    fn statistics_table(&self, f: &mut Formatter) -> Result {
+        let s = self.statistics;
+        let per_file = if s.secret_count > 0 && s.files_count > 0 { s.secret_count as f64 / s.files_count as f64 } else { 0.0 };
+        let (bytes, unit) = Scribe::bytes_human_readable(s.bytes_count);
+        writeln!(f, "<table><caption>Scan statistics</caption><tbody>")?;
+        writeln!(f, "<tr><td>Number of detectors used in scanning</td><td>{}</td></tr>", s.detectors_total_count)?;
+        writeln!(f, "<tr><td>Total found secrets</td><td>{}</td></tr>", s.secret_count)?;
+        writeln!(f, "<tr><td>Total suppressed secrets</td><td>{}</td></tr>", s.suppressed_count)?;
+        writeln!(f, "<tr><td>Scanned files</td><td>{}</td></tr>", s.files_count)?;
+        writeln!(f, "<tr><td>Skipped files</td><td>{}</td></tr>", s.skipped.len())?;
+        writeln!(f, "<tr><td>Leaked secrets per file</td><td>{:.4}</td></tr>", per_file)?;
+        writeln!(f, "<tr><td>{}</td><td>{:.3}</td></tr>", escape(&unit), bytes)?;
+        writeln!(f, "<tr><td>Processing time in milliseconds</td><td>{}</td></tr>", self.duration)?;
+        writeln!(f, "</tbody></table>")
+    }
+
+    #[inline(always)]
+    fn finding_row(&self, f: &mut Formatter, idx: usize, finding: &Secret) -> Result {
+        writeln!(f, "<tr>")?;
+        writeln!(f, "<td>{}</td>", idx + 1)?;
+        write!(f, "<td>{}", escape(&finding.detector_type.to_string()))?;
+        for tag in finding.tags.iter() {
+            write!(f, r#"<br><span class="tag">{}</span>"#, escape(tag))?;
+        }
+        writeln!(f, "</td>")?;
+        writeln!(f, "<td>{}</td>", escape(&finding.decoder_type.to_string()))?;
+        writeln!(f, r#"<td class="severity-{severity}" data-sort="{}">{severity}</td>"#, finding.severity as u8, severity = finding.severity)?;
+        writeln!(f, "<td>{:.2}</td>", finding.score)?;
+        writeln!(f, "<td>{}</td>", escape(&finding.file))?;
+        writeln!(f, "<td>{}</td>", finding.line)?;
+        writeln!(f, "<td>{}</td>", escape(&finding.branch))?;
+        writeln!(f, "<td>{}</td>", escape(finding.author.as_deref().unwrap_or(UNKNOWN)))?;
+        writeln!(f, "<td>{}</td>", escape(finding.commit.as_deref().unwrap_or_default()))?;
+        let raw_result = match self.redaction {
+            Redaction::None => finding.masked_raw_result(),
+            _ => finding.raw_result.clone(),
+        };
+        write!(f, "<td><code>{}</code>", escape(&raw_result))?;
+        if let Some(context) = &finding.context {
+            write!(f, "<pre>")?;
+            for (offset, line) in context.lines.iter().enumerate() {
+                let number = context.first_line + offset;
+                let class = if number == finding.line { "hit" } else { "muted" };
+                writeln!(f, r#"<span class="{class}">{number:>6} | {}</span>"#, escape(line))?;
+            }
+            write!(f, "</pre>")?;
+        }
+        if let Some(remediation) = &finding.remediation {
+            write!(f, "<p><b>Remediation:</b> {}</p>", escape(remediation))?;
+        }
+        for reference in finding.references.iter() {
+            match reference.starts_with("https://") || reference.starts_with("http://") {
+                true => write!(f, r#"<a href="{url}" rel="noopener noreferrer">{url}</a><br>"#, url = escape(reference))?,
+                false => write!(f, "{}<br>", escape(reference))?,
+            }
+        }
+        writeln!(f, "</td>")?;
+        writeln!(f, "</tr>")
+    }
+}
+
+impl Display for Report<'_> {
+    #[inline(always)]
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        writeln!(f, "<!DOCTYPE html>")?;
+        writeln!(f, r#"<html lang="en"><head><meta charset="utf-8"><title>{TITLE}</title><style>{STYLE}</style></head><body>"#)?;
+        writeln!(f, "<h1>{TITLE}</h1>")?;
+        writeln!(f, r#"<p class="muted">Generated {}</p>"#, self.generated.to_rfc3339())?;
+        writeln!(f, "<h2>Summary</h2>")?;
+        writeln!(f, r#"<div class="summary">"#)?;
+        self.counts_table(f, "Found secrets per decoder", "Decoder Type", &self.statistics.decoder_type_counts)?;
+        self.counts_table(f, "Found secrets per detector", "Detector Type", &self.statistics.detector_type_counts)?;
+        self.counts_table(f, "Found secrets per branch", "Branch Name", &self.statistics.branch_type_counts)?;
+        self.counts_table(f, "Found secrets per severity", "Severity", &self.statistics.severity_type_counts)?;
+        if !self.statistics.skip_reason_counts.is_empty() {
+            self.counts_table(f, "Skipped files per reason", "Reason", &self.statistics.skip_reason_counts)?;
+        }
+        self.statistics_table(f)?;
+        writeln!(f, "</div>")?;
+        writeln!(f, "<h2>Findings</h2>")?;
+        writeln!(f, r##"<table id="findings"><thead><tr><th>#</th><th>Detector</th><th>Decoder</th><th>Severity</th><th>Score</th><th>File</th><th>Line</th><th>Branch</th><th>Author</th><th>Commit</th><th>Secret</th></tr></thead><tbody>"##)?;
+        for (idx, finding) in self.findings.iter().enumerate() {
+            self.finding_row(f, idx, finding)?;
+        }
+        writeln!(f, "</tbody></table>")?;
+        if !self.statistics.skipped.is_empty() {
+            writeln!(f, "<h2>Skipped files</h2>")?;
+            writeln!(f, "<table><thead><tr><th>File</th><th>Reason</th></tr></thead><tbody>")?;
+            for skip in self.statistics.skipped.iter() {
+                writeln!(f, "<tr><td>{}</td><td>{}</td></tr>", escape(&skip.file), skip.reason)?;
+            }
+            writeln!(f, "</tbody></table>")?;
+        }
+        writeln!(f, "<script>{SCRIPT}</script>")?;
+        write!(f, "</body></html>")
+    }
+}
+
+#[inline(always)]
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
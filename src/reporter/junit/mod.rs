@@ -0,0 +1,94 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter, Result};
+use crate::result::{Secret, Skip};
+
+#[cfg(test)]
+mod mod_test;
+
+const SUITE_NAME: &str = "rogue";
+const FAILURE_TYPE: &str = "secret";
+
+/// Report is the JUnit XML report with one testcase per scanned file.
+/// Testcase of the file with findings fails, the failure lists all findings of the file.
+/// Files that weren't scanned are reported as skipped testcases.
+/// Raw secrets are not placed in the report.
+///
+#[derive(Debug)]
+pub(super) struct Report<'a> {
+    cases: BTreeMap<String, Vec<&'a Secret>>,
+    skipped: &'a [Skip],
+    failures: usize,
+    duration: u128,
+}
+
+impl<'a> Report<'a> {
+    /// Creates the report of scanned files and findings, files of the findings are reported even if not listed as scanned.
+    ///
+    #[inline(always)]
+    pub fn new(files: BTreeSet<String>, skipped: &'a [Skip], findings: &'a [Secret], duration: u128) -> Self {
+        let mut cases: BTreeMap<String, Vec<&'a Secret>> = files.into_iter().map(|f| (f, Vec::new())).collect();
+        for finding in findings {
+            cases.entry(finding.file.clone()).or_default().push(finding);
+        }
+        let failures = cases.values().filter(|f| !f.is_empty()).count();
+
+        Self { cases, skipped, failures, duration }
+    }
+}
+
+impl Display for Report<'_> {
+    #[inline(always)]
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let time = self.duration as f64 / 1000.0;
+        writeln!(f, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        let tests = self.cases.len() + self.skipped.len();
+        writeln!(f, r#"<testsuites name="{SUITE_NAME}" tests="{tests}" failures="{}" errors="0" time="{time:.3}">"#, self.failures)?;
+        writeln!(f, r#"  <testsuite name="{SUITE_NAME}" tests="{tests}" failures="{}" errors="0" skipped="{}" time="{time:.3}">"#, self.failures, self.skipped.len())?;
+        for (file, findings) in self.cases.iter() {
+            let file = escape(file);
+            if findings.is_empty() {
+                writeln!(f, r#"    <testcase classname="{SUITE_NAME}" name="{file}" file="{file}"/>"#)?;
+                continue;
+            }
+            writeln!(f, r#"    <testcase classname="{SUITE_NAME}" name="{file}" file="{file}">"#)?;
+            writeln!(f, r#"      <failure type="{FAILURE_TYPE}" message="Found {} secrets in {file}">"#, findings.len())?;
+            for finding in findings {
+                writeln!(f, "{}", escape(&format!(
+                    "Line {} - detector {}, decoder {}, score {:.2}, severity {}, branch {}",
+                    finding.line, finding.detector_type, finding.decoder_type, finding.score, finding.severity, finding.branch,
+                )))?;
+                if let Some(remediation) = &finding.remediation {
+                    writeln!(f, "{}", escape(&format!("  Remediation: {}", remediation)))?;
+                }
+            }
+            writeln!(f, "      </failure>")?;
+            writeln!(f, "    </testcase>")?;
+        }
+        for skip in self.skipped {
+            let file = escape(&skip.file);
+            writeln!(f, r#"    <testcase classname="{SUITE_NAME}" name="{file}" file="{file}">"#)?;
+            writeln!(f, r#"      <skipped message="Skipped due to {}"/>"#, skip.reason)?;
+            writeln!(f, "    </testcase>")?;
+        }
+        writeln!(f, "  </testsuite>")?;
+        write!(f, "</testsuites>")
+    }
+}
+
+#[inline(always)]
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c if c.is_control() && c != '\n' && c != '\t' => (),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
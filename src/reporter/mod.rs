@@ -1,21 +1,40 @@
 pub mod errors;
+mod gitlab;
+mod html;
+mod junit;
+mod sarif;
 
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::iter::Iterator;
 use std::rc::Rc;
 use std::sync::Mutex;
 use std::fmt::{Debug, Display};
 use std::io::Write;
+use std::path::PathBuf;
 use crossbeam_channel::{select, Receiver, Sender};
 use serde::Serialize;
-use crate::result::{Secret, DecoderType, DetectorType};
+use crate::result::{Secret, DecoderType, DetectorType, Rule, Severity, Skip, SkipReason};
+use errors::ReporterError;
+use crate::allowlist::secret_hash;
 use std::time::Instant;
+use chrono::Utc;
 use serde_json::to_string as to_json_string;
 use serde_yaml::to_string as to_yaml_string;
 
+#[cfg(test)]
+mod mod_test;
+
 const REPORT_HEADER: &str = "[ 📋 SCANNING REPORT 📋 ]";
 const REPORT_FOOTER: &str = "[ 📋 --------------- 📋 ]";
+const JSON_DOCUMENT_START: &str = "{\"findings\":[";
+const YAML_DOCUMENT_START: &str = "---";
+const REDACTED: &str = "[REDACTED]";
+const REDACTED_PARTIAL_FILL: &str = "****";
+const REDACTED_HASH_PREFIX: &str = "sha256:";
+const REDACTED_HASH_LEN: usize = 12;
+const REDACTION_PARTIAL_CHARS: usize = 4;
+const REDACTION_PARTIAL_MIN_HIDDEN: usize = 4;
 const GUESS_ANALITICS_CAPACITY: usize = 4096;
 const GUESS_CACHE_CAPACITY: usize = 1024 * 1000 * 8; // 1MB
 
@@ -36,14 +55,43 @@ pub enum Output {
 pub enum Format {
     Text,
     Json,
+    Ndjson,
     Yaml,
+    Sarif,
+    Junit,
+    Gitlab,
+    Html,
+}
+
+impl TryFrom<&str> for Format {
+    type Error = ReporterError;
+
+    #[inline(always)]
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.to_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "ndjson" => Ok(Self::Ndjson),
+            "yaml" => Ok(Self::Yaml),
+            "sarif" => Ok(Self::Sarif),
+            "junit" => Ok(Self::Junit),
+            "gitlab" => Ok(Self::Gitlab),
+            "html" => Ok(Self::Html),
+            _ => Err(ReporterError::UnknownFormat(value.to_string())),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub enum Input {
-    Finding(Secret),
+    Finding(Box<Secret>),
     Bytes(usize),
     Detectors(usize),
+    Suppressed(usize),
+    Rules(Vec<Rule>),
+    File(String),
+    Skipped(Skip),
+    Root(PathBuf),
 }
 
 /// Reporter reports received secrets to specified Output.
@@ -53,6 +101,137 @@ pub trait Reporter :Debug {
     fn set_output(&mut self, output: Output);
     /// Receive reads the output from the channel and formats the output passing it to preset Output.
     fn receive(&mut self, rx_secret: Receiver<Option<Input>>);
+    /// Returns the number of reported secrets, duplicates are not counted.
+    fn secrets_count(&self) -> usize;
+    /// Returns the number of reported secrets failing the threshold, zero if the threshold isn't reached.
+    fn failing_count(&self, threshold: &Threshold) -> usize;
+}
+
+/// Threshold specifies when the scan fails, either on the number of found secrets
+/// or on any secret of the given severity or more severe.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Threshold {
+    Count(usize),
+    Severity(Severity),
+}
+
+impl Default for Threshold {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::Count(1)
+    }
+}
+
+impl TryFrom<&str> for Threshold {
+    type Error = ReporterError;
+
+    #[inline(always)]
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        if let Ok(count) = value.parse::<usize>() {
+            return Ok(Self::Count(count));
+        }
+        match value.to_lowercase().as_str() {
+            "info" => Ok(Self::Severity(Severity::Info)),
+            "low" => Ok(Self::Severity(Severity::Low)),
+            "medium" => Ok(Self::Severity(Severity::Medium)),
+            "high" => Ok(Self::Severity(Severity::High)),
+            "critical" => Ok(Self::Severity(Severity::Critical)),
+            _ => Err(ReporterError::UnknownThreshold(value.to_string())),
+        }
+    }
+}
+
+/// Redaction specifies how the secret values are hidden in all the outputs.
+/// Partial keeps given number of the first and the last characters, hashed replaces the value with the prefix of its SHA-256.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Redaction {
+    None,
+    Full,
+    Partial(usize),
+    Hashed,
+}
+
+impl Default for Redaction {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::Partial(REDACTION_PARTIAL_CHARS)
+    }
+}
+
+impl TryFrom<&str> for Redaction {
+    type Error = ReporterError;
+
+    #[inline(always)]
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (policy, chars) = match value.split_once(':') {
+            Some((policy, chars)) => (policy, Some(chars)),
+            None => (value, None),
+        };
+        match (policy.to_lowercase().as_str(), chars) {
+            ("none", None) => Ok(Self::None),
+            ("full", None) => Ok(Self::Full),
+            ("hashed", None) => Ok(Self::Hashed),
+            ("partial", None) => Ok(Self::Partial(REDACTION_PARTIAL_CHARS)),
+            ("partial", Some(chars)) => match chars.parse::<usize>() {
+                Ok(n) => Ok(Self::Partial(n)),
+                Err(_) => Err(ReporterError::UnknownRedaction(value.to_string())),
+            },
+            _ => Err(ReporterError::UnknownRedaction(value.to_string())),
+        }
+    }
+}
+
+impl Redaction {
+    /// Redacts secret values in the raw result and in the context of the secret.
+    ///
+    #[inline(always)]
+    pub fn apply(&self, secret: &mut Secret) {
+        if *self == Self::None {
+            return;
+        }
+        let values: Vec<String> = secret.values().into_iter().map(|v| v.to_string()).collect();
+        for value in values.iter() {
+            let redacted = self.redact(value);
+            secret.raw_result = secret.raw_result.replace(value, &redacted);
+            if let Some(context) = secret.context.as_mut() {
+                for line in context.lines.iter_mut() {
+                    *line = line.replace(value, &redacted);
+                }
+            }
+        }
+    }
+
+    /// Redacts the single value, values too short to hide the middle part are redacted fully.
+    ///
+    #[inline(always)]
+    pub fn redact(&self, value: &str) -> String {
+        match self {
+            Self::None => value.to_string(),
+            Self::Full => REDACTED.to_string(),
+            Self::Hashed => format!("{}{}", REDACTED_HASH_PREFIX, &secret_hash(value)[..REDACTED_HASH_LEN]),
+            Self::Partial(n) => {
+                let chars: Vec<char> = value.chars().collect();
+                if chars.len() <= n * 2 + REDACTION_PARTIAL_MIN_HIDDEN {
+                    return REDACTED.to_string();
+                }
+                let head: String = chars[..*n].iter().collect();
+                let tail: String = chars[chars.len() - n..].iter().collect();
+                format!("{head}{REDACTED_PARTIAL_FILL}{tail}")
+            },
+        }
+    }
+}
+
+/// Record is the typed entry of the json, ndjson and yaml output, kind tells what the entry holds.
+///
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Record<'a> {
+    Finding(&'a Secret),
+    Statistics(&'a StatisticsSerializable),
+    Performance(&'a Performance),
 }
 
 #[derive(Debug, Serialize)]
@@ -67,10 +246,14 @@ struct Statistics {
     files_count: usize,
     bytes_count: usize,
     secret_count: usize,
+    suppressed_count: usize,
     detectors_total_count: usize,
     detector_type_counts: HashMap<DetectorType, usize>,
     decoder_type_counts: HashMap<DecoderType, usize>,
     branch_type_counts: HashMap<String, usize>,
+    severity_type_counts: HashMap<Severity, usize>,
+    skip_reason_counts: HashMap<SkipReason, usize>,
+    skipped: Vec<Skip>,
 }
 
 #[derive(Debug, Serialize)]
@@ -81,6 +264,8 @@ struct StatisticsSerializable {
     bytes_count: usize,
     #[serde(rename = "total_secret_found")]
     secret_count: usize,
+    #[serde(rename = "total_secret_suppressed")]
+    suppressed_count: usize,
     #[serde(rename = "number_of_used_detectors")]
     detectors_total_count: usize,
     #[serde(rename = "secret_found_per_detector")]
@@ -89,6 +274,14 @@ struct StatisticsSerializable {
     decoder_type_counts: HashMap<String, usize>,
     #[serde(rename = "secret_found_per_branch")]
     branch_type_counts: HashMap<String, usize>,
+    #[serde(rename = "secret_found_per_severity")]
+    severity_type_counts: HashMap<String, usize>,
+    #[serde(rename = "total_files_skipped")]
+    skipped_count: usize,
+    #[serde(rename = "files_skipped_per_reason")]
+    skip_reason_counts: HashMap<String, usize>,
+    #[serde(rename = "skipped_files")]
+    skipped: Vec<Skip>,
 }
 
 impl From<&Statistics> for StatisticsSerializable {
@@ -100,15 +293,22 @@ impl From<&Statistics> for StatisticsSerializable {
         decoder_type_counts.extend(s.decoder_type_counts.iter().map(|(k,v)| (k.to_string(), v.clone())));
         let mut branch_type_counts: HashMap<String, usize> = HashMap::with_capacity(s.decoder_type_counts.len());
         branch_type_counts.extend(s.branch_type_counts.iter().map(|(k,v)| (k.to_string(), v.clone())));
+        let severity_type_counts: HashMap<String, usize> = s.severity_type_counts.iter().map(|(k, v)| (k.to_string(), *v)).collect();
+        let skip_reason_counts: HashMap<String, usize> = s.skip_reason_counts.iter().map(|(k, v)| (k.to_string(), *v)).collect();
 
         Self {
             files_count: s.files_count,
             bytes_count: s.bytes_count,
             secret_count: s.secret_count,
+            suppressed_count: s.suppressed_count,
             detectors_total_count: s.detectors_total_count,
             detector_type_counts,
             decoder_type_counts,
             branch_type_counts,
+            severity_type_counts,
+            skipped_count: s.skipped.len(),
+            skip_reason_counts,
+            skipped: s.skipped.clone(),
         }
     }
 }
@@ -119,6 +319,7 @@ struct Scribe {
     output: Output,
     format: Format,
     statistics: Statistics,
+    redaction: Redaction,
     hasher: Option<fn (secret: &Secret) -> String>,
     deduplicator: RefCell<Option<HashSet<String>>>,
     timer: Option<Instant>,
@@ -130,6 +331,7 @@ impl Reporter for Scribe {
     fn receive(&mut self, rx_secret: Receiver<Option<Input>>) {
         match self.format {
             Format::Text => self.receive_for_format_text(rx_secret),
+            Format::Sarif | Format::Junit | Format::Gitlab | Format::Html => self.receive_for_format_document(rx_secret),
             _ => self.receive_for_fromat_with_parser(rx_secret),
         };
     }
@@ -138,60 +340,65 @@ impl Reporter for Scribe {
     fn set_output(&mut self, output: Output) {
         self.output = output;
     }
+
+    #[inline(always)]
+    fn secrets_count(&self) -> usize {
+        self.statistics.secret_count
+    }
+
+    #[inline(always)]
+    fn failing_count(&self, threshold: &Threshold) -> usize {
+        match threshold {
+            Threshold::Count(count) => if self.statistics.secret_count >= *count { self.statistics.secret_count } else { 0 },
+            Threshold::Severity(severity) => self.statistics.severity_type_counts.iter()
+                .filter(|(k, _)| *k >= severity)
+                .map(|(_, v)| *v)
+                .sum(),
+        }
+    }
 }
 
 impl Scribe {
-   #[inline(always)]
-    fn output_stats(&mut self) {
-        let statistic_ser: StatisticsSerializable = StatisticsSerializable::from(&self.statistics);
-        let parsed_stats = match self.format {
-            Format::Yaml => parse_yaml(&statistic_ser),
-            _ => parse_json(&statistic_ser),
+    /// Outputs the record in the document, ndjson line or yaml document.
+    /// Findings in json document are separated with comma from the previous finding.
+    ///
+    #[inline(always)]
+    fn output_record(&self, record: &Record, separate: bool) {
+        let parsed = match (&self.format, record) {
+            (Format::Json, Record::Finding(s)) => parse_json(s).map(|p| if separate { format!(",{p}") } else { p }),
+            (Format::Json, Record::Statistics(s)) => parse_json(s).map(|p| format!("],\"statistics\":{p},")),
+            (Format::Json, Record::Performance(p)) => parse_json(p).map(|p| format!("\"performance\":{p}}}")),
+            (Format::Yaml, record) => parse_yaml(record).map(|p| format!("{YAML_DOCUMENT_START}\n{}", p.trim_end())),
+            (_, record) => parse_json(record),
         };
-        let Some(parsed_stats) = parsed_stats else {
+        let Some(parsed) = parsed else {
             return;
         };
-        self.to_output(&parsed_stats);
-    }
-
-    #[inline(always)]
-    fn output_perf(&mut self) {
-        if let Some(timer) = self.timer{
-            let duration = timer.elapsed();
-            let parsed_perf = match self.format {
-                Format::Yaml => parse_yaml(&Performance{duration: duration.as_millis().to_owned()}),
-                _ => parse_json(&Performance{duration: duration.as_millis().to_owned()}),
-            };
-            let Some(parsed_stats) = parsed_perf else {
-                return;
-            };
-            self.to_output(&parsed_stats);
-        }
+        self.to_output(&parsed);
     }
 
     #[inline(always)]
     fn receive_for_fromat_with_parser(&mut self, rx_secret: Receiver<Option<Input>>) {
+        if let Format::Json = self.format {
+            self.to_output(&JSON_DOCUMENT_START);
+        }
+        let mut findings_count: usize = 0;
         'parser: loop {
             select! {
                 recv(rx_secret) -> message => match message {
                     Ok(m) => match m {
                         Some(s) => {
-                            if let None = self.timer {
+                            if self.timer.is_none() {
                                 self.timer = Some(Instant::now());
                             }
                             match s {
-                                Input::Finding(s) => {
+                                Input::Finding(mut s) => {
                                     if self.is_duplicate(&s) {
                                         continue 'parser;
                                     }
-                                    let parsed = match self.format {
-                                        Format::Yaml => parse_yaml(&s),
-                                        _ => parse_json(&s),
-                                    };
-                                    let Some(parsed) = parsed else {
-                                        continue 'parser;
-                                    };
-                                    self.to_output(&parsed);
+                                    self.redaction.apply(&mut s);
+                                    self.output_record(&Record::Finding(&s), findings_count > 0);
+                                    findings_count += 1;
                                     self.update_analitics(&s);
                                     if self.cache.borrow().len() > GUESS_CACHE_CAPACITY - 1024 {
                                         self.flush();
@@ -199,6 +406,11 @@ impl Scribe {
                                 },
                                 Input::Bytes(b) => self.update_files_scanned(b),
                                 Input::Detectors(c) => self.statistics.detectors_total_count = c,
+                                Input::Suppressed(c) => self.statistics.suppressed_count += c,
+                                Input::Rules(_) => (),
+                                Input::File(_) => (),
+                                Input::Skipped(s) => self.update_skipped(s),
+                                Input::Root(_) => (),
                             }
                         },
                         None => break 'parser,
@@ -207,8 +419,69 @@ impl Scribe {
                 },
             }
         }
-        self.output_stats();
-        self.output_perf();
+        self.output_record(&Record::Statistics(&StatisticsSerializable::from(&self.statistics)), false);
+        let duration = self.timer.map(|t| t.elapsed().as_millis()).unwrap_or_default();
+        self.output_record(&Record::Performance(&Performance { duration }), false);
+        self.flush()
+    }
+
+    /// Collects all the findings and outputs them as a single document once the input is closed.
+    ///
+    #[inline(always)]
+    fn receive_for_format_document(&mut self, rx_secret: Receiver<Option<Input>>) {
+        let started = Utc::now();
+        let mut rules: Vec<Rule> = Vec::new();
+        let mut files: BTreeSet<String> = BTreeSet::new();
+        let mut findings: Vec<Secret> = Vec::new();
+        let mut root: Option<PathBuf> = None;
+        'collector: loop {
+            select! {
+                recv(rx_secret) -> message => match message {
+                    Ok(m) => match m {
+                        Some(s) => {
+                            if self.timer.is_none() {
+                                self.timer = Some(Instant::now());
+                            }
+                            match s {
+                                Input::Finding(mut s) => {
+                                    if self.is_duplicate(&s) {
+                                        continue 'collector;
+                                    }
+                                    self.redaction.apply(&mut s);
+                                    self.update_analitics(&s);
+                                    findings.push(*s);
+                                },
+                                Input::Bytes(b) => self.update_files_scanned(b),
+                                Input::Detectors(c) => self.statistics.detectors_total_count = c,
+                                Input::Suppressed(c) => self.statistics.suppressed_count += c,
+                                Input::Rules(r) => rules.extend(r),
+                                Input::File(f) => {
+                                    files.insert(f);
+                                },
+                                Input::Skipped(s) => self.update_skipped(s),
+                                Input::Root(r) => root = Some(r),
+                            }
+                        },
+                        None => break 'collector,
+                    },
+                    Err(_) => break 'collector,
+                },
+            }
+        }
+        let duration = self.timer.map(|t| t.elapsed().as_millis()).unwrap_or_default();
+        let parsed = match self.format {
+            Format::Sarif => parse_json(&sarif::Log::new(rules, &findings, root.as_deref(), sarif::InvocationProperties {
+                statistics: StatisticsSerializable::from(&self.statistics),
+                performance: Performance { duration },
+            })),
+            Format::Junit => Some(junit::Report::new(files, &self.statistics.skipped, &findings, duration).to_string()),
+            Format::Gitlab => parse_json(&gitlab::Report::new(&rules, &findings, root.as_deref(), started, Utc::now())),
+            Format::Html => Some(html::Report::new(&self.statistics, &findings, self.redaction, duration, started).to_string()),
+            _ => None,
+        };
+        if let Some(parsed) = parsed {
+            self.to_output(&parsed);
+        }
         self.flush()
     }
 
@@ -224,10 +497,11 @@ impl Scribe {
                                 self.timer = Some(Instant::now());
                             }
                             match s {
-                                Input::Finding(s) => {
+                                Input::Finding(mut s) => {
                                     if self.is_duplicate(&s) {
                                         continue 'printer;
                                     }
+                                    self.redaction.apply(&mut s);
                                     self.to_output(&s);
                                     self.update_analitics(&s);
                                     if self.cache.borrow().len() > GUESS_CACHE_CAPACITY - 1024 {
@@ -236,6 +510,14 @@ impl Scribe {
                                 },
                                 Input::Bytes(b) => self.update_files_scanned(b),
                                 Input::Detectors(c) => self.statistics.detectors_total_count = c,
+                                Input::Suppressed(c) => self.statistics.suppressed_count += c,
+                                Input::Rules(_) => (),
+                                Input::File(_) => (),
+                                Input::Skipped(s) => {
+                                    self.to_output(&s);
+                                    self.update_skipped(s);
+                                },
+                                Input::Root(_) => (),
                             }
                         },
                         None => break 'printer,
@@ -285,6 +567,13 @@ impl Scribe {
         self.statistics.decoder_type_counts.entry(s.decoder_type.to_owned()).and_modify(|v| *v += 1).or_insert(1);
         self.statistics.detector_type_counts.entry(s.detector_type.to_owned()).and_modify(|v| *v += 1).or_insert(1);
         self.statistics.branch_type_counts.entry(s.branch.clone()).and_modify(|v| *v += 1).or_insert(1);
+        self.statistics.severity_type_counts.entry(s.severity).and_modify(|v| *v += 1).or_insert(1);
+    }
+
+    #[inline(always)]
+    fn update_skipped(&mut self, s: Skip) {
+        self.statistics.skip_reason_counts.entry(s.reason).and_modify(|v| *v += 1).or_insert(1);
+        self.statistics.skipped.push(s);
     }
 
     #[inline(always)]
@@ -292,10 +581,16 @@ impl Scribe {
         self.formatted_in_loop_to_output(self.statistics.decoder_type_counts.iter(), " FOUND SECRETS PER DECODER ", "Decoder Type");
         self.formatted_in_loop_to_output(self.statistics.detector_type_counts.iter(), " FOUND SECRETS PER DETECTOR ", "Detector Type");
         self.formatted_in_loop_to_output(self.statistics.branch_type_counts.iter(), " FOUND SECRETS PER BRANCH ", "Branch Name");
+        self.formatted_in_loop_to_output(
+            self.statistics.severity_type_counts.iter().collect::<BTreeMap<_, _>>().into_iter().rev(), " FOUND SECRETS PER SEVERITY ", "Severity");
+        self.formatted_in_loop_to_output(
+            self.statistics.skip_reason_counts.iter().collect::<BTreeMap<_, _>>().into_iter(), " SKIPPED FILES PER REASON ", "Reason");
         self.formatted_header(&" SCAN STATISTICS ");
         self.formatted_single_param(self.statistics.detectors_total_count, &"Number of detectors used in scanning");
         self.formatted_single_param(self.statistics.secret_count, &"Total found secrets");
+        self.formatted_single_param(self.statistics.suppressed_count, "Total suppressed secrets");
         self.formatted_single_param(self.statistics.files_count, &"Scanned files");
+        self.formatted_single_param(self.statistics.skipped.len(), "Skipped files");
         self.formatted_single_param(
             &format!("{:.4}", if self.statistics.secret_count > 0 && self.statistics.files_count > 0 { self.statistics.secret_count as f64 / self.statistics.files_count as f64 } else { 0.0 }),
             &" Leaked secrets per file");
@@ -368,23 +663,28 @@ impl Scribe {
     }
 }
 
-/// Creates new Reporter.
+/// Creates new Reporter, secrets are redacted according to the redaction policy in all the formats.
 #[inline(always)]
-pub fn new(output: Output, format: Format, dedup: u8) -> impl Reporter {
+pub fn new(output: Output, format: Format, dedup: u8, redaction: Redaction) -> impl Reporter {
     let statistics = Statistics {
         files_count: 0,
         bytes_count: 0,
         secret_count: 0,
+        suppressed_count: 0,
         detectors_total_count: 0,
         detector_type_counts: HashMap::with_capacity(GUESS_ANALITICS_CAPACITY),
         decoder_type_counts: HashMap::with_capacity(GUESS_ANALITICS_CAPACITY),
         branch_type_counts: HashMap::with_capacity(GUESS_ANALITICS_CAPACITY),
+        severity_type_counts: HashMap::with_capacity(GUESS_ANALITICS_CAPACITY),
+        skip_reason_counts: HashMap::new(),
+        skipped: Vec::new(),
     };
 
     return Scribe {
         output,
         format,
         statistics,
+        redaction,
         hasher: match dedup {
             0 => None,
             1 => Some(create_unique_key_level_branch),
@@ -0,0 +1,292 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use serde::Serialize;
+use crate::allowlist::secret_hash;
+use crate::result::{Rule, Secret, Severity};
+use super::{Performance, StatisticsSerializable};
+
+#[cfg(test)]
+mod mod_test;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+const TOOL_NAME: &str = "rogue";
+const TOOL_URI: &str = "https://github.com/OpenSourceScannerCollective/static_scanner";
+const FINGERPRINT_KEY: &str = "rogue/v1";
+const SOURCE_ROOT: &str = "%SRCROOT%";
+
+/// InvocationProperties holds the run statistics reported in the SARIF invocation.
+///
+#[derive(Debug, Serialize)]
+pub(super) struct InvocationProperties {
+    #[serde(flatten)]
+    pub statistics: StatisticsSerializable,
+    #[serde(flatten)]
+    pub performance: Performance,
+}
+
+/// Log is the SARIF 2.1.0 log with a single run of the scanner.
+///
+#[derive(Debug, Serialize)]
+pub(super) struct Log {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run {
+    tool: Tool,
+    invocations: Vec<Invocation>,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Serialize)]
+struct Tool {
+    driver: Driver,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver {
+    name: &'static str,
+    version: &'static str,
+    information_uri: &'static str,
+    rules: Vec<ReportingDescriptor>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportingDescriptor {
+    id: String,
+    name: String,
+    short_description: Message,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    full_description: Option<Message>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help: Option<Message>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help_uri: Option<String>,
+    default_configuration: Configuration,
+    properties: RuleProperties,
+}
+
+#[derive(Debug, Serialize)]
+struct Configuration {
+    level: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct RuleProperties {
+    tags: Vec<String>,
+    #[serde(rename = "security-severity")]
+    security_severity: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Invocation {
+    execution_successful: bool,
+    properties: InvocationProperties,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: String,
+    rule_index: usize,
+    level: &'static str,
+    message: Message,
+    locations: Vec<Location>,
+    partial_fingerprints: BTreeMap<&'static str, String>,
+    properties: ResultProperties,
+}
+
+#[derive(Debug, Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    physical_location: PhysicalLocation,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region: Option<Region>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactLocation {
+    uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uri_base_id: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct ResultProperties {
+    branch: String,
+    decoder: String,
+    score: f64,
+    severity: Severity,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    commit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<BTreeMap<String, String>>,
+}
+
+impl Log {
+    /// Creates the log with a rule per given rule and a result per finding.
+    /// Findings of detectors without a given rule get a rule with the detector name only.
+    /// Files are located relative to the scanned root, so code scanning can link them to the files of the repository.
+    /// Raw secrets are not placed in the log, results are identified by the partial fingerprint.
+    ///
+    #[inline(always)]
+    pub fn new(rules: Vec<Rule>, findings: &[Secret], root: Option<&Path>, properties: InvocationProperties) -> Self {
+        let mut descriptors: Vec<ReportingDescriptor> = Vec::with_capacity(rules.len());
+        let mut indexes: HashMap<String, usize> = HashMap::with_capacity(rules.len());
+        for rule in rules {
+            register(&mut descriptors, &mut indexes, rule);
+        }
+
+        let mut results: Vec<SarifResult> = Vec::with_capacity(findings.len());
+        for finding in findings {
+            let rule_id = finding.detector_type.to_string();
+            let rule_index = match indexes.get(&rule_id) {
+                Some(idx) => *idx,
+                None => register(&mut descriptors, &mut indexes, Rule {
+                    id: rule_id.clone(),
+                    severity: finding.severity,
+                    tags: finding.tags.clone(),
+                    remediation: finding.remediation.clone(),
+                    references: finding.references.clone(),
+                    ..Default::default()
+                }),
+            };
+            results.push(SarifResult {
+                message: Message { text: format!("Secret found by {} detector in {} data with score {:.2}.", rule_id, finding.decoder_type, finding.score) },
+                rule_id,
+                rule_index,
+                level: level(finding.severity),
+                locations: vec![Location {
+                    physical_location: PhysicalLocation {
+                        artifact_location: artifact_location(&finding.relative_file(root)),
+                        region: match finding.line {
+                            0 => None,
+                            line => Some(Region { start_line: line }),
+                        },
+                    },
+                }],
+                partial_fingerprints: BTreeMap::from([(
+                    FINGERPRINT_KEY,
+                    finding.fingerprint.clone().unwrap_or_else(|| secret_hash(&finding.raw_result)),
+                )]),
+                properties: ResultProperties {
+                    branch: finding.branch.clone(),
+                    decoder: finding.decoder_type.to_string(),
+                    score: finding.score,
+                    severity: finding.severity,
+                    tags: finding.tags.clone(),
+                    author: finding.author.clone(),
+                    commit: finding.commit.clone(),
+                    metadata: finding.metadata.clone(),
+                },
+            });
+        }
+
+        Self {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: vec![Run {
+                tool: Tool {
+                    driver: Driver {
+                        name: TOOL_NAME,
+                        version: env!("CARGO_PKG_VERSION"),
+                        information_uri: TOOL_URI,
+                        rules: descriptors,
+                    },
+                },
+                invocations: vec![Invocation { execution_successful: true, properties }],
+                results,
+            }],
+        }
+    }
+}
+
+#[inline(always)]
+fn register(descriptors: &mut Vec<ReportingDescriptor>, indexes: &mut HashMap<String, usize>, rule: Rule) -> usize {
+    if let Some(idx) = indexes.get(&rule.id) {
+        return *idx;
+    }
+    let idx = descriptors.len();
+    indexes.insert(rule.id.clone(), idx);
+    descriptors.push(ReportingDescriptor {
+        name: rule.id.clone(),
+        short_description: Message { text: format!("{} secret", rule.id) },
+        full_description: rule.description.map(|text| Message { text }),
+        help: rule.remediation.map(|text| Message { text }),
+        help_uri: rule.references.into_iter().next(),
+        default_configuration: Configuration { level: level(rule.severity) },
+        properties: RuleProperties {
+            tags: rule.groups.into_iter().chain(rule.tags).collect(),
+            security_severity: security_severity(rule.severity),
+        },
+        id: rule.id,
+    });
+
+    idx
+}
+
+/// Maps the severity to the SARIF level, only high and critical findings are errors.
+///
+#[inline(always)]
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical | Severity::High => "error",
+        Severity::Medium => "warning",
+        Severity::Low | Severity::Info => "note",
+    }
+}
+
+/// Maps the severity to the security severity score code scanning dashboards use to rank the rules.
+///
+#[inline(always)]
+fn security_severity(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "9.5",
+        Severity::High => "8.0",
+        Severity::Medium => "5.5",
+        Severity::Low => "2.0",
+        Severity::Info => "0.0",
+    }
+}
+
+/// Relative paths are located against the source root base, absolute paths outside of the scanned root are turned in to file URIs.
+///
+#[inline(always)]
+fn artifact_location(file: &str) -> ArtifactLocation {
+    let uri = file.replace(' ', "%20");
+    if Path::new(file).is_absolute() {
+        return ArtifactLocation { uri: format!("file://{}", uri), uri_base_id: None };
+    }
+
+    ArtifactLocation { uri, uri_base_id: Some(SOURCE_ROOT) }
+}
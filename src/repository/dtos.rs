@@ -1,5 +1,6 @@
 use std::time::SystemTime;
 use serde::{Deserialize, Serialize};
+use crate::result::Severity;
 use super::errors::RepositoryError;
 
 /// This functionality returns bytes that are used to create a signature.
@@ -86,17 +87,34 @@ pub struct KeysWithSecretsDto {
 }
 
 /// ConfigDTO transfers secret config inftormation.
+/// Key paths, lexer, entropy filters, severity, tags, remediation and references are skipped when not set, so signatures of configs without them stay valid.
 ///
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub struct ConfigDto {
     pub secret_regexes: Option<Vec<String>>,
     pub keys_with_secrets: Option<Vec<KeysWithSecretsDto>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_paths: Option<Vec<KeysWithSecretsDto>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lexer: Option<bool>,
     pub keys_required: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_entropy: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub charset: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub references: Option<Vec<String>>,
 }
 
 /// RegexConfigurationDto transfers all information required to create regex config in repository.
 ///
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct RegexConfigurationCreateDto {
     pub name: String,
     pub description: String,
@@ -154,7 +172,7 @@ impl AsBytesToSigned for RegexConfigurationDeleteDto {
 
 /// RegexConfigurationDataDto trensfers regex config information stored in repository to the application client.
 ///
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct RegexConfigurationDataDto {
     pub id: i32,
     pub name: String,
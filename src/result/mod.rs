@@ -1,16 +1,28 @@
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter, Result};
+use std::path::Path;
 use serde::{Deserialize, Serialize};
 
 #[cfg(test)]
 mod mod_test;
 
+const RAW_RESULT_SEPARATOR: &str = ", ";
+const RAW_RESULT_KEY_SEPARATOR: &str = ": ";
+const MASK_CHAR: char = '*';
+const MASK_VISIBLE_PREFIX: usize = 4;
+const MASK_MIN_LEN_WITH_PREFIX: usize = 8;
+
 /// Describes detector type.
 ///
 #[derive(Debug, Serialize, Deserialize, Display, Clone, PartialEq, Eq, Hash)]
 #[display(fmt = "{}")]
 pub enum DetectorType {
     #[serde(rename = "Configured")]
-    Unique(String)
+    Unique(String),
+    #[serde(rename = "Builtin")]
+    Builtin(String),
+    #[serde(rename = "Fingerprint")]
+    Fingerprint(String),
 }
 
 /// Describes decoder type.
@@ -21,14 +33,49 @@ pub enum DecoderType {
     #[display(fmt = "Plane")]
     #[serde(rename = "Plane")]
     Plane,
+    #[display(fmt = "Hex")]
+    #[serde(rename = "Hex")]
+    Hex,
+    #[display(fmt = "Base32")]
+    #[serde(rename = "Base32")]
+    Base32,
+    #[display(fmt = "Base58")]
+    #[serde(rename = "Base58")]
+    Base58,
     #[display(fmt = "Base64")]
-    #[serde(rename = "Nase64")]
+    #[serde(rename = "Base64")]
     Base64,
+    #[display(fmt = "Base85")]
+    #[serde(rename = "Base85")]
+    Base85,
     #[display(fmt = "JWT")]
     #[serde(rename = "JWT")]
     Jwt,
 }
 
+/// Describes severity of the finding, ordered from the least to the most severe.
+///
+#[derive(Debug, Serialize, Deserialize, Display, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[display(fmt = "{}")]
+pub enum Severity {
+    #[display(fmt = "info")]
+    #[serde(rename = "info")]
+    Info,
+    #[display(fmt = "low")]
+    #[serde(rename = "low")]
+    Low,
+    #[default]
+    #[display(fmt = "medium")]
+    #[serde(rename = "medium")]
+    Medium,
+    #[display(fmt = "high")]
+    #[serde(rename = "high")]
+    High,
+    #[display(fmt = "critical")]
+    #[serde(rename = "critical")]
+    Critical,
+}
+
 /// Result of the secret finding.
 ///
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -36,18 +83,200 @@ pub struct Secret {
     pub detector_type: DetectorType,
     pub decoder_type: DecoderType,
     pub raw_result: String,
+    #[serde(default)]
+    pub score: f64,
+    #[serde(default)]
+    pub severity: Severity,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<String>,
     pub branch: String,
     pub file: String,
     pub line: usize,
     pub author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<BTreeMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<Context>,
+}
+
+impl Secret {
+    /// Attributes the secret to the author of the commit.
+    ///
+    #[inline(always)]
+    pub fn attribute(&mut self, commit: Commit) {
+        self.commit = Some(commit.hash);
+        self.commit_time = commit.time;
+        self.author = commit.author;
+        self.email = commit.email;
+    }
+
+    /// Returns the file relative to the scanned root with forward slashes, so it is the same on every machine and workspace.
+    /// File that isn't under the root is returned as is, the root being the scanned file itself gives the file name.
+    ///
+    #[inline(always)]
+    pub fn relative_file(&self, root: Option<&Path>) -> String {
+        let path = Path::new(&self.file);
+        let relative = match root.map(|r| path.strip_prefix(r)) {
+            Some(Ok(relative)) if relative.as_os_str().is_empty() => path.file_name().map(Path::new).unwrap_or(path),
+            Some(Ok(relative)) => relative,
+            _ => path,
+        };
+
+        relative.to_string_lossy().replace('\\', "/").trim_start_matches("./").to_string()
+    }
+
+    /// Returns the secret values of the raw result, raw result lists values as `key: value` separated with comma.
+    ///
+    #[inline(always)]
+    pub fn values(&self) -> Vec<&str> {
+        self.raw_result
+            .split(RAW_RESULT_SEPARATOR)
+            .map(|item| item.split_once(RAW_RESULT_KEY_SEPARATOR).map(|(_, v)| v).unwrap_or(item))
+            .filter(|v| !v.is_empty())
+            .collect()
+    }
+
+    /// Returns the raw result with all the secret values masked.
+    ///
+    #[inline(always)]
+    pub fn masked_raw_result(&self) -> String {
+        let mut masked = self.raw_result.clone();
+        for value in self.values() {
+            masked = masked.replace(value, &mask(value));
+        }
+
+        masked
+    }
+}
+
+/// Masks the value keeping only a short prefix of the long values, so the value can be recognised but not used.
+///
+#[inline(always)]
+pub fn mask(value: &str) -> String {
+    let len = value.chars().count();
+    let visible = if len > MASK_MIN_LEN_WITH_PREFIX { MASK_VISIBLE_PREFIX } else { 0 };
+
+    value.chars().take(visible).chain(std::iter::repeat_n(MASK_CHAR, len - visible)).collect()
+}
+
+/// Context holds the lines surrounding the secret with the secret values masked.
+/// First line is the line number of the first context line in the file.
+///
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct Context {
+    pub first_line: usize,
+    pub lines: Vec<String>,
+}
+
+/// Commit describes the commit that introduced the secret.
+/// Time is formatted in RFC-3339.
+///
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct Commit {
+    pub hash: String,
+    pub author: Option<String>,
+    pub email: Option<String>,
+    pub time: Option<String>,
+}
+
+/// SkipReason tells why the file wasn't scanned, which limit it hit or that its content is binary.
+///
+#[derive(Debug, Serialize, Deserialize, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+#[display(fmt = "{}")]
+pub enum SkipReason {
+    #[display(fmt = "file size")]
+    FileSize,
+    #[display(fmt = "archive size")]
+    ArchiveSize,
+    #[display(fmt = "compression ratio")]
+    CompressionRatio,
+    #[display(fmt = "entry count")]
+    EntryCount,
+    #[display(fmt = "nesting depth")]
+    Depth,
+    #[display(fmt = "binary content")]
+    Binary,
+}
+
+/// Skip describes the file that wasn't scanned and the reason.
+///
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Skip {
+    pub file: String,
+    pub reason: SkipReason,
+}
+
+impl Display for Skip {
+    #[inline(always)]
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        writeln!(f, "⚠️ Skipped file:\nFile [ {} ]\nReason [ {} ]", self.file, self.reason)
+    }
+}
+
+/// Rule describes the detector reporting findings, its id matches the detector type of the finding.
+/// Remediation tells what to do with the found secret, references are URLs with more information about the secret.
+///
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct Rule {
+    pub id: String,
+    pub description: Option<String>,
+    pub groups: Vec<String>,
+    #[serde(default)]
+    pub severity: Severity,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub remediation: Option<String>,
+    #[serde(default)]
+    pub references: Vec<String>,
 }
 
 impl Display for Secret {
     #[inline(always)]
     fn fmt(&self, f: &mut Formatter) -> Result {
-        write!(f, "😱 Found secret:\nDetector Type [ {} ]\nDecoderType [ {} ]\nRawResult [ {} ]\nBranch [ {} ]\nFile [ {} ]\nLine [ {} ]\nAuthor [ {} ]\n",
-            self.detector_type, self.decoder_type, self.raw_result,
-            self.branch, self.file, self.line, self.author.clone().unwrap_or("unknown".to_string()),
-        )
+        write!(f, "😱 Found secret:\nDetector Type [ {} ]\nDecoderType [ {} ]\nRawResult [ {} ]\nScore [ {:.2} ]\nSeverity [ {} ]\nBranch [ {} ]\n",
+            self.detector_type, self.decoder_type, self.raw_result, self.score, self.severity, self.branch,
+        )?;
+        if let Some(commit) = &self.commit {
+            writeln!(f, "Commit [ {} ]", commit)?;
+        }
+        if let Some(commit_time) = &self.commit_time {
+            writeln!(f, "Commit Time [ {} ]", commit_time)?;
+        }
+        write!(f, "File [ {} ]\nLine [ {} ]\nAuthor [ {} ]\n",
+            self.file, self.line, self.author.clone().unwrap_or("unknown".to_string()),
+        )?;
+        if let Some(email) = &self.email {
+            writeln!(f, "Email [ {} ]", email)?;
+        }
+        if let Some(metadata) = &self.metadata {
+            writeln!(f, "Metadata [ {} ]", metadata.iter().map(|(k, v)| format!("{k}: {v}")).collect::<Vec<String>>().join(", "))?;
+        }
+        if let Some(fingerprint) = &self.fingerprint {
+            writeln!(f, "Fingerprint [ {} ]", fingerprint)?;
+        }
+        if !self.tags.is_empty() {
+            writeln!(f, "Tags [ {} ]", self.tags.join(", "))?;
+        }
+        if let Some(remediation) = &self.remediation {
+            writeln!(f, "Remediation [ {} ]", remediation)?;
+        }
+        if !self.references.is_empty() {
+            writeln!(f, "References [ {} ]", self.references.join(", "))?;
+        }
+        Ok(())
     }
 }
@@ -18,4 +18,6 @@ pub enum SourceError {
     GitSourceNotReady(String),
     #[error("failed due to not being able to access the {0} branch")]
     BranchNotAccessible(String),
+    #[error("failed due to existing hook {0}, overwrite it with force")]
+    HookExists(String),
 }
@@ -1,6 +1,15 @@
-use git2::{BranchType, Repository, build::CheckoutBuilder};
-use std::path::PathBuf;
-use std::{fs::remove_dir_all, env::temp_dir};
+use chrono::{DateTime, FixedOffset};
+use git2::{BlameOptions, BranchType, Diff, ObjectType, Oid, Patch, Repository, Sort, Tree, TreeWalkMode, TreeWalkResult, build::CheckoutBuilder};
+use std::collections::HashSet;
+use std::fmt::{Debug, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use crate::filter::{PathFilter, IGNORE_FILES};
+use crate::inspect::Attributor;
+use crate::result::Commit;
+use std::{fs::{create_dir_all, remove_dir_all, write}, env::temp_dir};
+#[cfg(unix)]
+use std::{fs::{set_permissions, Permissions}, os::unix::fs::PermissionsExt};
 use random_string::generate;
 use super::{RepositoryProvider, errors::SourceError};
 
@@ -92,6 +101,102 @@ impl RepositoryProvider for GitRepo {
         Ok(())
     }
 
+    #[inline(always)]
+    fn tree(&self, reference: &str) -> Result<RefTree, SourceError> {
+        let Some(repo) = &self.repo else {
+            return Err(SourceError::GitSourceNotReady("Repository is flushed or doesn't exist.".to_string()));
+        };
+
+        Ok(RefTree {
+            repo: Repository::open(repo.path())?,
+            reference: reference.to_string(),
+        })
+    }
+
+    #[inline(always)]
+    fn history(&self, branches: &[String], range: &CommitRange) -> Result<History, SourceError> {
+        let Some(repo) = &self.repo else {
+            return Err(SourceError::GitSourceNotReady("Repository is flushed or doesn't exist.".to_string()));
+        };
+
+        let heads = match &range.until_commit {
+            Some(until) => vec![peel_to_commit_id(repo, until)?],
+            None => branches.iter().map(|b| peel_to_commit_id(repo, b)).collect::<Result<Vec<Oid>, SourceError>>()?,
+        };
+        let hidden = match &range.since_commit {
+            Some(since) => Some(peel_to_commit_id(repo, since)?),
+            None => None,
+        };
+
+        Ok(History {
+            repo: Repository::open(repo.path())?,
+            heads,
+            hidden,
+            range: range.clone(),
+        })
+    }
+
+    #[inline(always)]
+    fn diff(&self, base: &str, head: &str) -> Result<BranchDiff, SourceError> {
+        let Some(repo) = &self.repo else {
+            return Err(SourceError::GitSourceNotReady("Repository is flushed or doesn't exist.".to_string()));
+        };
+
+        Ok(BranchDiff {
+            repo: Repository::open(repo.path())?,
+            base: peel_to_commit_id(repo, base)?,
+            head: peel_to_commit_id(repo, head)?,
+        })
+    }
+
+    #[inline(always)]
+    fn staged(&self) -> Result<Staged, SourceError> {
+        let Some(repo) = &self.repo else {
+            return Err(SourceError::GitSourceNotReady("Repository is flushed or doesn't exist.".to_string()));
+        };
+
+        Ok(Staged {
+            repo: Repository::open(repo.path())?,
+        })
+    }
+
+    /// Writes the hook to the directory set by core.hooksPath, relative to the working directory if not absolute,
+    /// or to the hooks directory of the repository. Existing hook is overwritten only if forced.
+    ///
+    #[inline(always)]
+    fn install_hook(&self, name: &str, script: &str, force: bool) -> Result<PathBuf, SourceError> {
+        let Some(repo) = &self.repo else {
+            return Err(SourceError::GitSourceNotReady("Repository is flushed or doesn't exist.".to_string()));
+        };
+        let hooks = match repo.config()?.get_path("core.hooksPath") {
+            Ok(hooks) if hooks.is_absolute() => hooks,
+            Ok(hooks) => repo.workdir().unwrap_or(repo.path()).join(hooks),
+            Err(_) => repo.path().join("hooks"),
+        };
+        create_dir_all(&hooks)?;
+        let hook = hooks.join(name);
+        if !force && hook.exists() {
+            return Err(SourceError::HookExists(hook.to_str().unwrap_or_default().to_string()));
+        }
+        write(&hook, script)?;
+        #[cfg(unix)]
+        set_permissions(&hook, Permissions::from_mode(0o755))?;
+
+        Ok(hook)
+    }
+
+    #[inline(always)]
+    fn blamer(&self) -> Result<Blamer, SourceError> {
+        let Some(repo) = &self.repo else {
+            return Err(SourceError::GitSourceNotReady("Repository is flushed or doesn't exist.".to_string()));
+        };
+
+        Ok(Blamer {
+            repo: Mutex::new(Repository::open(repo.path())?),
+            workdir: repo.workdir().map(PathBuf::from),
+        })
+    }
+
     #[inline(always)]
     fn flush(&mut self) -> Result<(), SourceError> {
         if self.is_local {
@@ -108,3 +213,416 @@ impl RepositoryProvider for GitRepo {
         Ok(())
     }
 }
+
+/// Blob holds the content of the file read from the git object database.
+///
+#[derive(Debug, Clone)]
+pub struct Blob {
+    pub file: String,
+    pub content: Vec<u8>,
+}
+
+/// RefTree reads files straight from the tree of the ref without checking it out,
+/// so the working copy of the repository is never modified.
+///
+pub struct RefTree {
+    repo: Repository,
+    reference: String,
+}
+
+impl RefTree {
+    /// Walks the tree of the ref calling f for each file that isn't excluded by the filter.
+    /// Excluded directories are not descended into, ignore files are read from the tree.
+    ///
+    #[inline(always)]
+    pub fn walk(&self, filter: &PathFilter, mut f: impl FnMut(Blob)) -> Result<(), SourceError> {
+        let tree = self.repo.revparse_single(&self.reference)?.peel_to_tree()?;
+        let filter = with_ignore_files(&self.repo, &tree, filter);
+        let mut result: Result<(), SourceError> = Ok(());
+        tree.walk(TreeWalkMode::PreOrder, |root, entry| {
+            let Some(name) = entry.name() else {
+                return TreeWalkResult::Skip;
+            };
+            let is_dir = match entry.kind() {
+                Some(ObjectType::Tree) => true,
+                Some(ObjectType::Blob) => false,
+                _ => return TreeWalkResult::Skip,
+            };
+            let file = format!("{root}{name}");
+            if filter.is_excluded(&file, is_dir) {
+                return TreeWalkResult::Skip;
+            }
+            if is_dir {
+                return TreeWalkResult::Ok;
+            }
+            match self.repo.find_blob(entry.id()) {
+                Ok(blob) => f(Blob { file, content: blob.content().to_vec() }),
+                Err(e) => {
+                    result = Err(e.into());
+                    return TreeWalkResult::Abort;
+                },
+            };
+            TreeWalkResult::Ok
+        })?;
+
+        result
+    }
+}
+
+/// Chunk holds lines added to a single blob, by a commit if known.
+/// Each line of the data is mapped to the line number in the blob.
+///
+#[derive(Debug, Clone)]
+pub struct Chunk {
+    pub commit: Option<Commit>,
+    pub file: String,
+    pub data: String,
+    pub lines: Vec<usize>,
+}
+
+/// CommitRange limits the history to commits made after the since commit up to the until commit,
+/// with commit time between since and until given in seconds from the unix epoch.
+///
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CommitRange {
+    pub since_commit: Option<String>,
+    pub until_commit: Option<String>,
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+}
+
+impl CommitRange {
+    /// Returns true if the range doesn't limit the history.
+    ///
+    #[inline(always)]
+    pub fn is_unbounded(&self) -> bool {
+        self.since_commit.is_none() && self.until_commit.is_none() && self.since.is_none() && self.until.is_none()
+    }
+
+    #[inline(always)]
+    fn contains_time(&self, seconds: i64) -> bool {
+        if let Some(since) = self.since {
+            if seconds < since {
+                return false;
+            }
+        }
+        if let Some(until) = self.until {
+            if seconds > until {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// History walks the commit graph of the repository and reads the lines added by every commit.
+/// Each line of a blob is read only once, no matter how many commits add it.
+/// Heads and the hidden commit are resolved when the history is created, so the mistyped ref fails before the walk.
+///
+pub struct History {
+    repo: Repository,
+    heads: Vec<Oid>,
+    hidden: Option<Oid>,
+    range: CommitRange,
+}
+
+impl History {
+    /// Walks commits reachable from the until commit, the branches, or from HEAD if none of them is given,
+    /// calling f for each blob that has added lines and isn't excluded by the filter extended with the ignore files of HEAD.
+    /// Commits reachable from the since commit and commits outside of the time range are skipped.
+    /// Merge commits are diffed against the first parent, keeping only the lines that are absent from every other parent,
+    /// so the lines brought in by the merged branch stay credited to the commits that added them
+    /// and only the lines changed while resolving conflicts are read with the merge commit.
+    ///
+    #[inline(always)]
+    pub fn walk(&self, filter: &PathFilter, mut f: impl FnMut(Chunk)) -> Result<(), SourceError> {
+        let filter = with_head_ignore_files(&self.repo, filter);
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+        if self.heads.is_empty() {
+            revwalk.push_head()?;
+        }
+        for head in self.heads.iter() {
+            revwalk.push(*head)?;
+        }
+        if let Some(hidden) = self.hidden {
+            revwalk.hide(hidden)?;
+        }
+
+        let mut seen: HashSet<(Oid, usize)> = HashSet::new();
+        for oid in revwalk {
+            let commit = self.repo.find_commit(oid?)?;
+            if !self.range.contains_time(commit.time().seconds()) {
+                continue;
+            }
+            let tree = commit.tree()?;
+            let parent_tree = match commit.parent_count() {
+                0 => None,
+                _ => Some(commit.parent(0)?.tree()?),
+            };
+            let mut merged: Option<HashSet<(String, usize)>> = None;
+            for parent in commit.parents().skip(1) {
+                let diff = self.repo.diff_tree_to_tree(Some(&parent.tree()?), Some(&tree), None)?;
+                let added = added_line_numbers(&diff)?;
+                merged = Some(match merged {
+                    Some(lines) => lines.intersection(&added).cloned().collect(),
+                    None => added,
+                });
+            }
+            let diff = self.repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+            walk_diff(&diff, &filter, &mut seen, merged.as_ref(), Some(&commit_info(&commit)), &mut f)?;
+        }
+
+        Ok(())
+    }
+
+}
+
+/// BranchDiff reads the lines added in the head relative to the merge base of the base and the head.
+/// The base and the head are resolved when the diff is created, so the mistyped ref fails before the walk.
+///
+pub struct BranchDiff {
+    repo: Repository,
+    base: Oid,
+    head: Oid,
+}
+
+impl BranchDiff {
+    /// Walks the diff between the merge base and the head,
+    /// calling f for each file that has added lines and isn't excluded by the filter extended with the ignore files of the head.
+    ///
+    #[inline(always)]
+    pub fn walk(&self, filter: &PathFilter, mut f: impl FnMut(Chunk)) -> Result<(), SourceError> {
+        let merge_base = self.repo.merge_base(self.base, self.head)?;
+        let old_tree = self.repo.find_commit(merge_base)?.tree()?;
+        let new_tree = self.repo.find_commit(self.head)?.tree()?;
+        let diff = self.repo.diff_tree_to_tree(Some(&old_tree), Some(&new_tree), None)?;
+
+        walk_diff(&diff, &with_ignore_files(&self.repo, &new_tree, filter), &mut HashSet::new(), None, None, &mut f)
+    }
+}
+
+/// Staged reads the lines added in the index relative to the HEAD, that is the content about to be committed.
+///
+pub struct Staged {
+    repo: Repository,
+}
+
+impl Staged {
+    /// Walks the diff between the HEAD and the index,
+    /// calling f for each staged file that has added lines and isn't excluded by the filter extended with the ignore files of HEAD.
+    /// In the repository without commits all the staged files are walked.
+    ///
+    #[inline(always)]
+    pub fn walk(&self, filter: &PathFilter, mut f: impl FnMut(Chunk)) -> Result<(), SourceError> {
+        let head = match self.repo.head() {
+            Ok(head) => Some(head.peel_to_tree()?),
+            Err(_) => None,
+        };
+        let index = self.repo.index()?;
+        let diff = self.repo.diff_tree_to_index(head.as_ref(), Some(&index), None)?;
+        let filter = match &head {
+            Some(tree) => with_ignore_files(&self.repo, tree, filter),
+            None => filter.clone(),
+        };
+
+        walk_diff(&diff, &filter, &mut HashSet::new(), None, None, &mut f)
+    }
+}
+
+#[inline(always)]
+fn peel_to_commit_id(repo: &Repository, spec: &str) -> Result<Oid, SourceError> {
+    let (object, _reference) = repo.revparse_ext(spec)?;
+    Ok(object.peel_to_commit()?.id())
+}
+
+/// Returns the filter extended with the ignore files of the HEAD tree, repository without commits has none.
+///
+#[inline(always)]
+fn with_head_ignore_files(repo: &Repository, filter: &PathFilter) -> PathFilter {
+    match repo.head().and_then(|h| h.peel_to_tree()) {
+        Ok(tree) => with_ignore_files(repo, &tree, filter),
+        Err(_) => filter.clone(),
+    }
+}
+
+/// Returns the filter extended with the ignore files found in the tree outside of the excluded directories.
+///
+#[inline(always)]
+fn with_ignore_files(repo: &Repository, tree: &Tree, filter: &PathFilter) -> PathFilter {
+    let mut filter = filter.clone();
+    if !filter.honors_ignore_files() {
+        return filter;
+    }
+    let _ = tree.walk(TreeWalkMode::PreOrder, |root, entry| {
+        let Some(name) = entry.name() else {
+            return TreeWalkResult::Skip;
+        };
+        match entry.kind() {
+            Some(ObjectType::Tree) if filter.is_excluded(&format!("{root}{name}"), true) => return TreeWalkResult::Skip,
+            Some(ObjectType::Blob) if IGNORE_FILES.contains(&name) => {
+                if let Ok(blob) = repo.find_blob(entry.id()) {
+                    filter.add_ignore_file(root, &String::from_utf8_lossy(blob.content()));
+                }
+            },
+            _ => (),
+        };
+        TreeWalkResult::Ok
+    });
+
+    filter
+}
+
+/// Calls f for each blob of the diff that isn't excluded by the filter with the added lines that weren't seen before.
+/// Lines are identified by the blob and the line number, so the same blob reached by another diff is read
+/// only for the lines not added yet. When merged lines are given, only the lines of the file listed there are read.
+///
+#[inline(always)]
+fn walk_diff(
+    diff: &Diff,
+    filter: &PathFilter,
+    seen: &mut HashSet<(Oid, usize)>,
+    merged: Option<&HashSet<(String, usize)>>,
+    commit: Option<&Commit>,
+    f: &mut impl FnMut(Chunk),
+) -> Result<(), SourceError> {
+    'deltas: for (idx, delta) in diff.deltas().enumerate() {
+        let blob = delta.new_file().id();
+        if blob.is_zero() {
+            continue 'deltas;
+        }
+        let Some(file) = delta.new_file().path().and_then(|p| p.to_str()) else {
+            continue 'deltas;
+        };
+        if filter.is_excluded(file, false) {
+            continue 'deltas;
+        }
+        let file = file.to_string();
+
+        let Some(patch) = Patch::from_diff(diff, idx)? else {
+            continue 'deltas;
+        };
+        let keep = |line: usize| merged.is_none_or(|m| m.contains(&(file.clone(), line))) && seen.insert((blob, line));
+        let Some((data, lines)) = added_lines(&patch, keep)? else {
+            continue 'deltas;
+        };
+        f(Chunk { commit: commit.cloned(), file, data, lines });
+    }
+
+    Ok(())
+}
+
+/// Returns the file and the line number of every line added by the diff, skipping binary files.
+///
+#[inline(always)]
+fn added_line_numbers(diff: &Diff) -> Result<HashSet<(String, usize)>, SourceError> {
+    let mut added = HashSet::new();
+    for idx in 0..diff.deltas().len() {
+        let Some(patch) = Patch::from_diff(diff, idx)? else {
+            continue;
+        };
+        let Some(file) = patch.delta().new_file().path().and_then(|p| p.to_str()).map(|p| p.to_string()) else {
+            continue;
+        };
+        if let Some((_, lines)) = added_lines(&patch, |_| true)? {
+            added.extend(lines.into_iter().map(|line| (file.clone(), line)));
+        }
+    }
+
+    Ok(added)
+}
+
+/// Blamer resolves the commits that introduced lines of files using git blame.
+///
+pub struct Blamer {
+    repo: Mutex<Repository>,
+    workdir: Option<PathBuf>,
+}
+
+impl Debug for Blamer {
+    #[inline(always)]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Blamer").field("workdir", &self.workdir).finish()
+    }
+}
+
+impl Attributor for Blamer {
+    /// Blames the line of the file as it is in the branch, or in HEAD if the branch cannot be resolved.
+    ///
+    #[inline(always)]
+    fn attribute(&self, branch: &str, file: &str, line: usize) -> Option<Commit> {
+        if line == 0 {
+            return None;
+        }
+        let repo = self.repo.lock().ok()?;
+        let path = Path::new(file);
+        let path = match &self.workdir {
+            Some(workdir) => path.strip_prefix(workdir).unwrap_or(path),
+            None => path,
+        };
+        let newest = match repo.revparse_single(branch) {
+            Ok(object) => object,
+            Err(_) => repo.revparse_single("HEAD").ok()?,
+        };
+        let mut options = BlameOptions::new();
+        options.newest_commit(newest.peel_to_commit().ok()?.id());
+        let blame = repo.blame_file(path, Some(&mut options)).ok()?;
+        let hunk = blame.get_line(line)?;
+        let commit = repo.find_commit(hunk.final_commit_id()).ok()?;
+
+        Some(commit_info(&commit))
+    }
+}
+
+/// Reads hash, author and time of the commit.
+///
+#[inline(always)]
+fn commit_info(commit: &git2::Commit) -> Commit {
+    let author = commit.author();
+    let time = commit.time();
+    let time = FixedOffset::east_opt(time.offset_minutes() * 60)
+        .and_then(|offset| DateTime::from_timestamp(time.seconds(), 0).map(|t| t.with_timezone(&offset).to_rfc3339()));
+
+    Commit {
+        hash: commit.id().to_string(),
+        author: author.name().map(|n| n.to_string()),
+        email: author.email().map(|e| e.to_string()),
+        time,
+    }
+}
+
+/// Collects lines added in the patch that are accepted by the keep function, joined by new line,
+/// with their line numbers in the new file. Returns None if patch is binary or has no such lines.
+///
+#[inline(always)]
+fn added_lines(patch: &Patch, mut keep: impl FnMut(usize) -> bool) -> Result<Option<(String, Vec<usize>)>, SourceError> {
+    if patch.delta().flags().is_binary() {
+        return Ok(None);
+    }
+    let mut data = String::new();
+    let mut lines = Vec::new();
+    for hunk in 0..patch.num_hunks() {
+        for idx in 0..patch.num_lines_in_hunk(hunk)? {
+            let line = patch.line_in_hunk(hunk, idx)?;
+            if line.origin() != '+' {
+                continue;
+            }
+            let Some(number) = line.new_lineno() else {
+                continue;
+            };
+            if !keep(number as usize) {
+                continue;
+            }
+            if !lines.is_empty() {
+                data.push('\n');
+            }
+            data.push_str(String::from_utf8_lossy(line.content()).trim_end_matches(['\n', '\r']));
+            lines.push(number as usize);
+        }
+    }
+    if lines.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some((data, lines)))
+}
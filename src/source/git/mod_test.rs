@@ -1,7 +1,93 @@
 mod tests {
-    use crate::source::{RepositoryProvider, git::GitRepo};
+    use crate::filter::PathFilter;
+    use crate::inspect::Attributor;
+    use crate::source::{RepositoryProvider, git::{CommitRange, GitRepo, walk_diff}};
+    use git2::{Oid, Repository, Signature, Time, build::CheckoutBuilder};
+    use std::collections::HashSet;
+    use random_string::generate;
+    use std::{env::temp_dir, fs::{create_dir_all, read_to_string, remove_dir_all, write}, path::PathBuf};
 
     const TEST_URL: &str = "https://github.com/OpenSourceScannerCollective/expired-creds.git";
+    const TEST_SECRET: &str = "password = 'Qp+*'!ruZ89pyD'";
+
+    /// Fixture is the temporary repository removed when dropped.
+    ///
+    struct Fixture {
+        dir: PathBuf,
+        repo: Repository,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let mut dir = PathBuf::from(temp_dir().as_path());
+            dir.push(format!("rogue_test_repo_{}", generate(12, crate::source::CHARSET)));
+            let repo = Repository::init(&dir).expect("init repository");
+            Self { dir, repo }
+        }
+
+        fn local(&self) -> GitRepo {
+            GitRepo::local(&self.dir).expect("open local repository")
+        }
+
+        fn write(&self, file: &str, content: &str) {
+            let path = self.dir.join(file);
+            if let Some(parent) = path.parent() {
+                create_dir_all(parent).expect("create directory");
+            }
+            write(path, content).expect("write file");
+        }
+
+        fn stage(&self, file: &str) {
+            let mut index = self.repo.index().expect("open index");
+            index.add_path(&PathBuf::from(file)).expect("add file to index");
+            index.write().expect("write index");
+        }
+
+        fn commit(&self, file: &str, content: &str) -> Oid {
+            self.commit_with_signature(file, content, &Signature::now("Test Author", "test@author.com").expect("signature"))
+        }
+
+        fn commit_at(&self, file: &str, content: &str, seconds: i64) -> Oid {
+            self.commit_with_signature(file, content, &Signature::new("Test Author", "test@author.com", &Time::new(seconds, 0)).expect("signature"))
+        }
+
+        fn commit_with_signature(&self, file: &str, content: &str, signature: &Signature) -> Oid {
+            self.write(file, content);
+            self.stage(file);
+            let mut index = self.repo.index().expect("open index");
+            let tree = self.repo.find_tree(index.write_tree().expect("write tree")).expect("find tree");
+            let parent = self.repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents = parent.iter().collect::<Vec<_>>();
+            self.repo.commit(Some("HEAD"), signature, signature, "test commit", &tree, &parents).expect("commit")
+        }
+
+        fn branch(&self, name: &str, at: Oid) {
+            let commit = self.repo.find_commit(at).expect("find commit");
+            self.repo.branch(name, &commit, false).expect("create branch");
+        }
+
+        fn checkout(&self, name: &str) {
+            self.repo.set_head(&format!("refs/heads/{name}")).expect("set head");
+            self.repo.checkout_head(Some(CheckoutBuilder::new().force())).expect("checkout head");
+        }
+
+        fn head(&self) -> String {
+            self.repo.head().expect("head").shorthand().unwrap_or_default().to_string()
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = remove_dir_all(&self.dir);
+        }
+    }
+
+    fn exclude(pattern: &str) -> PathFilter {
+        let mut filter = PathFilter::new(true);
+        let _ = filter.with_exclude(pattern);
+        filter
+    }
+
     #[test]
     fn it_should_fetch_repo() {
         let Ok(mut repo) = GitRepo::remote(TEST_URL) else {
@@ -79,4 +165,287 @@ mod tests {
             return;
         };
     }
+
+    #[test]
+    fn it_should_walk_history_and_find_removed_lines() {
+        let fixture = Fixture::new();
+        let first = fixture.commit("config.txt", &format!("user = admin\n{}\n", TEST_SECRET));
+        let _ = fixture.commit("config.txt", "user = admin\n");
+
+        let history = fixture.local().history(&[], &CommitRange::default()).expect("history");
+        let mut chunks = Vec::new();
+        history.walk(&PathFilter::default(), |chunk| chunks.push(chunk)).expect("walk history");
+
+        assert_eq!(chunks.len(), 1);
+        let commit = chunks[0].commit.as_ref().expect("commit of the chunk");
+        assert_eq!(commit.hash, first.to_string());
+        assert_eq!(commit.author, Some("Test Author".to_string()));
+        assert_eq!(commit.email, Some("test@author.com".to_string()));
+        assert_eq!(chunks[0].file, "config.txt");
+        assert_eq!(chunks[0].lines, vec![1, 2]);
+        assert!(chunks[0].data.contains(TEST_SECRET));
+    }
+
+    #[test]
+    fn it_should_walk_history_and_read_each_blob_once() {
+        let fixture = Fixture::new();
+        let _ = fixture.commit("config.txt", &format!("{}\n", TEST_SECRET));
+        let _ = fixture.commit("config.txt", "nothing here\n");
+        let _ = fixture.commit("config.txt", &format!("{}\n", TEST_SECRET));
+        let _ = fixture.commit("omitted.txt", &format!("{}\n", TEST_SECRET));
+
+        let history = fixture.local().history(&[], &CommitRange::default()).expect("history");
+        let mut chunks = Vec::new();
+        history.walk(&exclude("omitted.txt"), |chunk| chunks.push(chunk)).expect("walk history");
+
+        assert_eq!(chunks.iter().filter(|c| c.data.contains(TEST_SECRET)).count(), 1);
+        assert_eq!(chunks.len(), 2);
+    }
+
+    #[test]
+    fn it_should_read_lines_of_seen_blob_added_by_another_diff() {
+        let fixture = Fixture::new();
+        let first = fixture.commit("config.txt", &format!("{}\n", TEST_SECRET));
+        let second = fixture.commit("config.txt", &format!("{}\ntail\n", TEST_SECRET));
+        let third = fixture.commit("copy.txt", &format!("{}\ntail\n", TEST_SECRET));
+        let tree = |oid: Oid| fixture.repo.find_commit(oid).and_then(|c| c.tree()).expect("tree of commit");
+        let appended = fixture.repo.diff_tree_to_tree(Some(&tree(first)), Some(&tree(second)), None).expect("diff");
+        let copied = fixture.repo.diff_tree_to_tree(Some(&tree(second)), Some(&tree(third)), None).expect("diff");
+
+        let mut seen = HashSet::new();
+        let mut chunks = Vec::new();
+        walk_diff(&appended, &PathFilter::default(), &mut seen, None, None, &mut |chunk| chunks.push(chunk)).expect("walk diff");
+        walk_diff(&copied, &PathFilter::default(), &mut seen, None, None, &mut |chunk| chunks.push(chunk)).expect("walk diff");
+        walk_diff(&copied, &PathFilter::default(), &mut seen, None, None, &mut |chunk| chunks.push(chunk)).expect("walk diff");
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!((chunks[0].file.as_str(), chunks[0].data.as_str(), chunks[0].lines.clone()), ("config.txt", "tail", vec![2]));
+        assert_eq!((chunks[1].file.as_str(), chunks[1].data.as_str(), chunks[1].lines.clone()), ("copy.txt", TEST_SECRET, vec![1]));
+    }
+
+    #[test]
+    fn it_should_credit_merged_lines_to_the_commit_that_added_them() {
+        let fixture = Fixture::new();
+        let base = fixture.commit("config.txt", "user = admin\n");
+        fixture.branch("feature", base);
+        let _ = fixture.commit("master.txt", "master\n");
+        let master = fixture.head();
+        fixture.checkout("feature");
+        let leaker = Signature::now("Leaker", "leaker@author.com").expect("signature");
+        let leaked = fixture.commit_with_signature("config.txt", &format!("user = admin\n{}\n", TEST_SECRET), &leaker);
+        fixture.checkout(&master);
+        let merger = Signature::now("Merger", "merger@author.com").expect("signature");
+        let ours = fixture.repo.head().and_then(|h| h.peel_to_commit()).expect("head commit");
+        let theirs = fixture.repo.find_commit(leaked).expect("find commit");
+        let mut index = fixture.repo.merge_commits(&ours, &theirs, None).expect("merge commits");
+        let tree = fixture.repo.find_tree(index.write_tree_to(&fixture.repo).expect("write tree")).expect("find tree");
+        let _ = fixture.repo.commit(Some("HEAD"), &merger, &merger, "merge feature", &tree, &[&ours, &theirs]).expect("merge commit");
+
+        let history = fixture.local().history(&[], &CommitRange::default()).expect("history");
+        let mut chunks = Vec::new();
+        history.walk(&PathFilter::default(), |chunk| chunks.push(chunk)).expect("walk history");
+
+        let leaks = chunks.iter().filter(|c| c.data.contains(TEST_SECRET)).collect::<Vec<_>>();
+        assert_eq!(leaks.len(), 1);
+        let commit = leaks[0].commit.as_ref().expect("commit of the chunk");
+        assert_eq!(commit.hash, leaked.to_string());
+        assert_eq!(commit.author, Some("Leaker".to_string()));
+    }
+
+    #[test]
+    fn it_should_walk_history_between_commits() {
+        let fixture = Fixture::new();
+        let first = fixture.commit("first.txt", "first\n");
+        let second = fixture.commit("second.txt", "second\n");
+        let _ = fixture.commit("third.txt", "third\n");
+
+        let range = CommitRange {
+            since_commit: Some(first.to_string()),
+            until_commit: Some(second.to_string()),
+            ..Default::default()
+        };
+        let history = fixture.local().history(&[], &range).expect("history");
+        let mut chunks = Vec::new();
+        history.walk(&PathFilter::default(), |chunk| chunks.push(chunk)).expect("walk history");
+
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].commit.as_ref().map(|c| c.hash.clone()), Some(second.to_string()));
+        assert_eq!(chunks[0].file, "second.txt");
+    }
+
+    #[test]
+    fn it_should_fail_on_unknown_history_refs() {
+        let fixture = Fixture::new();
+        let _ = fixture.commit("first.txt", "first\n");
+        let local = fixture.local();
+
+        let since = CommitRange { since_commit: Some("deadbeef".to_string()), ..Default::default() };
+        assert!(local.history(&[], &since).is_err());
+        let until = CommitRange { until_commit: Some("deadbeef".to_string()), ..Default::default() };
+        assert!(local.history(&[], &until).is_err());
+        assert!(local.history(&["no-such-branch".to_string()], &CommitRange::default()).is_err());
+        assert!(local.history(&[fixture.head()], &CommitRange::default()).is_ok());
+    }
+
+    #[test]
+    fn it_should_walk_history_within_time_range() {
+        let fixture = Fixture::new();
+        let _ = fixture.commit_at("first.txt", "first\n", 1000);
+        let second = fixture.commit_at("second.txt", "second\n", 2000);
+        let _ = fixture.commit_at("third.txt", "third\n", 3000);
+
+        let range = CommitRange {
+            since: Some(1500),
+            until: Some(2500),
+            ..Default::default()
+        };
+        let history = fixture.local().history(&[], &range).expect("history");
+        let mut chunks = Vec::new();
+        history.walk(&PathFilter::default(), |chunk| chunks.push(chunk)).expect("walk history");
+
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].commit.as_ref().map(|c| c.hash.clone()), Some(second.to_string()));
+    }
+
+    #[test]
+    fn it_should_blame_line_author() {
+        let fixture = Fixture::new();
+        let first = fixture.commit_at("config.txt", "user = admin\n", 1000);
+        let second = fixture.commit_at("config.txt", &format!("user = admin\n{}\n", TEST_SECRET), 2000);
+
+        let blamer = fixture.local().blamer().expect("blamer");
+        let file = fixture.dir.join("config.txt");
+        let commit = blamer.attribute("HEAD", file.to_str().unwrap_or_default(), 2).expect("commit of the second line");
+        assert_eq!(commit.hash, second.to_string());
+        assert_eq!(commit.author, Some("Test Author".to_string()));
+        assert_eq!(commit.email, Some("test@author.com".to_string()));
+        assert_eq!(commit.time, Some("1970-01-01T00:33:20+00:00".to_string()));
+
+        let commit = blamer.attribute("HEAD", "config.txt", 1).expect("commit of the first line");
+        assert_eq!(commit.hash, first.to_string());
+
+        assert!(blamer.attribute("HEAD", "config.txt", 3).is_none());
+    }
+
+    #[test]
+    fn it_should_walk_diff_from_merge_base_to_head() {
+        let fixture = Fixture::new();
+        let base = fixture.commit("config.txt", "user = admin\nhost = localhost\n");
+        fixture.branch("feature", base);
+        let _ = fixture.commit("master.txt", &format!("{}\n", TEST_SECRET));
+        let master = fixture.head();
+        fixture.checkout("feature");
+        let _ = fixture.commit("config.txt", &format!("user = admin\n{}\nhost = localhost\n", TEST_SECRET));
+
+        let diff = fixture.local().diff(&master, "feature").expect("diff");
+        let mut chunks = Vec::new();
+        diff.walk(&PathFilter::default(), |chunk| chunks.push(chunk)).expect("walk diff");
+
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].file, "config.txt");
+        assert_eq!(chunks[0].data, TEST_SECRET);
+        assert_eq!(chunks[0].lines, vec![2]);
+        assert!(chunks[0].commit.is_none());
+    }
+
+    #[test]
+    fn it_should_fail_on_unknown_diff_refs() {
+        let fixture = Fixture::new();
+        let _ = fixture.commit("config.txt", "user = admin\n");
+        let local = fixture.local();
+
+        assert!(local.diff("nonexistent", "HEAD").is_err());
+        assert!(local.diff("HEAD", "nonexistent").is_err());
+        assert!(local.diff("HEAD", "HEAD").is_ok());
+    }
+
+    #[test]
+    fn it_should_read_branch_tree_without_checkout() {
+        let fixture = Fixture::new();
+        let base = fixture.commit("config.txt", "user = admin\n");
+        fixture.branch("feature", base);
+        let _ = fixture.commit("node_modules/lib.txt", &format!("{}\n", TEST_SECRET));
+        let _ = fixture.commit("config.txt", &format!("user = admin\n{}\n", TEST_SECRET));
+
+        let local = fixture.local();
+        let tree = local.tree("feature").expect("tree of the branch");
+        let mut blobs = Vec::new();
+        tree.walk(&PathFilter::default(), |blob| blobs.push(blob)).expect("walk tree");
+        assert_eq!(blobs.len(), 1);
+        assert_eq!(blobs[0].file, "config.txt");
+        assert_eq!(blobs[0].content, b"user = admin\n");
+
+        let tree = local.tree("HEAD").expect("tree of HEAD");
+        let mut blobs = Vec::new();
+        tree.walk(&exclude("node_modules/"), |blob| blobs.push(blob)).expect("walk tree");
+        assert_eq!(blobs.len(), 1);
+        assert_eq!(blobs[0].file, "config.txt");
+
+        let data = read_to_string(fixture.dir.join("config.txt")).expect("read working copy");
+        assert_eq!(data, format!("user = admin\n{}\n", TEST_SECRET));
+        assert_ne!(fixture.head(), "feature");
+    }
+
+    #[test]
+    fn it_should_honor_ignore_files_of_the_tree() {
+        let fixture = Fixture::new();
+        let _ = fixture.commit(".gitignore", "*.log\n");
+        let _ = fixture.commit("app/.rogueignore", "build/\n");
+        let _ = fixture.commit("app/build/out.txt", &format!("{}\n", TEST_SECRET));
+        let _ = fixture.commit("app/debug.log", &format!("{}\n", TEST_SECRET));
+        let _ = fixture.commit("app/config.txt", &format!("{}\n", TEST_SECRET));
+
+        let tree = fixture.local().tree("HEAD").expect("tree of HEAD");
+        let mut files = Vec::new();
+        tree.walk(&PathFilter::new(true), |blob| files.push(blob.file)).expect("walk tree");
+        assert_eq!(files, vec![".gitignore".to_string(), "app/.rogueignore".to_string(), "app/config.txt".to_string()]);
+
+        let mut files = Vec::new();
+        tree.walk(&PathFilter::new(false), |blob| files.push(blob.file)).expect("walk tree");
+        assert_eq!(files.len(), 5);
+    }
+
+    #[test]
+    fn it_should_walk_staged_lines_only() {
+        let fixture = Fixture::new();
+        let _ = fixture.commit("config.txt", "user = admin\n");
+        fixture.write("config.txt", &format!("user = admin\n{}\n", TEST_SECRET));
+        fixture.write("unstaged.txt", &format!("{}\n", TEST_SECRET));
+        fixture.stage("config.txt");
+
+        let staged = fixture.local().staged().expect("staged");
+        let mut chunks = Vec::new();
+        staged.walk(&PathFilter::default(), |chunk| chunks.push(chunk)).expect("walk staged");
+
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].file, "config.txt");
+        assert_eq!(chunks[0].data, TEST_SECRET);
+        assert_eq!(chunks[0].lines, vec![2]);
+    }
+
+    #[test]
+    fn it_should_install_hook() {
+        let fixture = Fixture::new();
+        let local = fixture.local();
+        let script = "#!/bin/sh\nexit 0\n";
+        let hook = local.install_hook("pre-commit", script, false).expect("install hook");
+        assert!(hook.ends_with(".git/hooks/pre-commit"));
+        assert_eq!(read_to_string(&hook).expect("read hook"), script);
+
+        let forced = "#!/bin/sh\nexit 1\n";
+        assert!(local.install_hook("pre-commit", forced, false).is_err());
+        assert_eq!(read_to_string(&hook).expect("read hook"), script);
+        local.install_hook("pre-commit", forced, true).expect("install forced hook");
+        assert_eq!(read_to_string(&hook).expect("read hook"), forced);
+    }
+
+    #[test]
+    fn it_should_install_hook_to_configured_hooks_path() {
+        let fixture = Fixture::new();
+        fixture.repo.config().and_then(|mut c| c.set_str("core.hooksPath", ".githooks")).expect("set hooks path");
+        let script = "#!/bin/sh\nexit 0\n";
+        let hook = fixture.local().install_hook("pre-commit", script, false).expect("install hook");
+        assert_eq!(hook, fixture.dir.join(".githooks/pre-commit"));
+        assert_eq!(read_to_string(&hook).expect("read hook"), script);
+    }
 }
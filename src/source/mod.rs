@@ -4,7 +4,7 @@ pub mod errors;
 use std::path::PathBuf;
 use walkdir::WalkDir;
 use crate::source::errors::SourceError;
-use crate::source::git::GitRepo;
+use crate::source::git::{Blamer, BranchDiff, CommitRange, GitRepo, History, RefTree, Staged};
 
 
 const TEMP_DIR_REPO: &str = "rogue_temp_repo_files";
@@ -24,6 +24,12 @@ pub trait RepositoryProvider {
     fn get_local_branches(&self) -> Result<Vec<String>, SourceError>;
     fn get_remote_branches(&self) -> Result<Vec<String>, SourceError>;
     fn switch_branch(&self, branch: &str) -> Result<(), SourceError>;
+    fn tree(&self, reference: &str) -> Result<RefTree, SourceError>;
+    fn history(&self, branches: &[String], range: &CommitRange) -> Result<History, SourceError>;
+    fn diff(&self, base: &str, head: &str) -> Result<BranchDiff, SourceError>;
+    fn staged(&self) -> Result<Staged, SourceError>;
+    fn install_hook(&self, name: &str, script: &str, force: bool) -> Result<PathBuf, SourceError>;
+    fn blamer(&self) -> Result<Blamer, SourceError>;
 }
 
 /// Source wrapps around the concreate source provider.
@@ -111,6 +117,60 @@ impl RepositoryProvider for Source {
             Self::Local(gr) => gr.switch_branch(branch),
         }
     }
+
+    #[inline(always)]
+    fn tree(&self, reference: &str) -> Result<RefTree, SourceError> {
+        match self {
+            Self::FileSystem(_) => Err(SourceError::ParameterFailure("No access to branches on filesystem".to_string())),
+            Self::Remote(gr) => gr.tree(reference),
+            Self::Local(gr) => gr.tree(reference),
+        }
+    }
+
+    #[inline(always)]
+    fn history(&self, branches: &[String], range: &CommitRange) -> Result<History, SourceError> {
+        match self {
+            Self::FileSystem(_) => Err(SourceError::ParameterFailure("No access to history on filesystem".to_string())),
+            Self::Remote(gr) => gr.history(branches, range),
+            Self::Local(gr) => gr.history(branches, range),
+        }
+    }
+
+    #[inline(always)]
+    fn diff(&self, base: &str, head: &str) -> Result<BranchDiff, SourceError> {
+        match self {
+            Self::FileSystem(_) => Err(SourceError::ParameterFailure("No access to branches on filesystem".to_string())),
+            Self::Remote(gr) => gr.diff(base, head),
+            Self::Local(gr) => gr.diff(base, head),
+        }
+    }
+
+    #[inline(always)]
+    fn staged(&self) -> Result<Staged, SourceError> {
+        match self {
+            Self::FileSystem(_) => Err(SourceError::ParameterFailure("No access to index on filesystem".to_string())),
+            Self::Remote(gr) => gr.staged(),
+            Self::Local(gr) => gr.staged(),
+        }
+    }
+
+    #[inline(always)]
+    fn install_hook(&self, name: &str, script: &str, force: bool) -> Result<PathBuf, SourceError> {
+        match self {
+            Self::FileSystem(_) => Err(SourceError::ParameterFailure("No access to hooks on filesystem".to_string())),
+            Self::Remote(gr) => gr.install_hook(name, script, force),
+            Self::Local(gr) => gr.install_hook(name, script, force),
+        }
+    }
+
+    #[inline(always)]
+    fn blamer(&self) -> Result<Blamer, SourceError> {
+        match self {
+            Self::FileSystem(_) => Err(SourceError::ParameterFailure("No access to blame on filesystem".to_string())),
+            Self::Remote(gr) => gr.blamer(),
+            Self::Local(gr) => gr.blamer(),
+        }
+    }
 }
 
 /// Branch level specifies the level at which Git repo is scanned.
@@ -0,0 +1,32 @@
+use super::{join, strip_comment, unquote, Entry};
+
+/// Parses INI and dot env files, keys of the section are prefixed with the section name.
+///
+#[inline(always)]
+pub(super) fn parse(s: &str) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    let mut section = String::new();
+
+    for (idx, raw) in s.lines().enumerate() {
+        let content = raw.trim();
+        if content.is_empty() || content.starts_with(['#', ';']) {
+            continue;
+        }
+        if let Some(name) = content.strip_prefix('[').and_then(|c| c.strip_suffix(']')) {
+            section = name.trim().to_string();
+            continue;
+        }
+        let content = content.strip_prefix("export ").map(str::trim_start).unwrap_or(content);
+        let Some(separator) = content.find(['=', ':']) else {
+            continue;
+        };
+        let key = content[..separator].trim();
+        if key.is_empty() {
+            continue;
+        }
+        let value = strip_comment(content[separator + 1..].trim(), &['#', ';']).trim();
+        entries.push(Entry { path: join(&section, key), value: unquote(value).to_string(), line: idx + 1 });
+    }
+
+    entries
+}
@@ -0,0 +1,153 @@
+use super::{index, join, Entry};
+
+/// Parses JSON document, strings, numbers and booleans are the values, nulls are skipped.
+///
+#[inline(always)]
+pub(super) fn parse(s: &str) -> Vec<Entry> {
+    let mut parser = Parser { s, pos: 0, line: 1, entries: Vec::new() };
+    let _ = parser.value("");
+
+    parser.entries
+}
+
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+    line: usize,
+    entries: Vec<Entry>,
+}
+
+impl Parser<'_> {
+    #[inline(always)]
+    fn peek(&self) -> Option<u8> {
+        self.s.as_bytes().get(self.pos).copied()
+    }
+
+    #[inline(always)]
+    fn skip_whitespace(&mut self) {
+        while let Some(b) = self.peek() {
+            match b {
+                b'\n' => self.line += 1,
+                b' ' | b'\t' | b'\r' => (),
+                _ => return,
+            };
+            self.pos += 1;
+        }
+    }
+
+    fn value(&mut self, path: &str) -> Option<()> {
+        self.skip_whitespace();
+        match self.peek()? {
+            b'{' => {
+                self.pos += 1;
+                loop {
+                    self.skip_whitespace();
+                    match self.peek()? {
+                        b'}' => {
+                            self.pos += 1;
+                            return Some(());
+                        },
+                        b',' => self.pos += 1,
+                        b'"' => {
+                            let key = self.string()?;
+                            self.skip_whitespace();
+                            if self.peek()? != b':' {
+                                return None;
+                            }
+                            self.pos += 1;
+                            self.value(&join(path, &key))?;
+                        },
+                        _ => return None,
+                    };
+                }
+            },
+            b'[' => {
+                self.pos += 1;
+                let mut idx = 0;
+                loop {
+                    self.skip_whitespace();
+                    match self.peek()? {
+                        b']' => {
+                            self.pos += 1;
+                            return Some(());
+                        },
+                        b',' => self.pos += 1,
+                        _ => {
+                            self.value(&index(path, idx))?;
+                            idx += 1;
+                        },
+                    };
+                }
+            },
+            b'"' => {
+                let line = self.line;
+                let value = self.string()?;
+                self.entries.push(Entry { path: path.to_string(), value, line });
+                Some(())
+            },
+            _ => {
+                let start = self.pos;
+                while self.peek().is_some_and(|b| !matches!(b, b',' | b'}' | b']' | b' ' | b'\t' | b'\r' | b'\n')) {
+                    self.pos += 1;
+                }
+                match &self.s[start..self.pos] {
+                    "" => None,
+                    "null" => Some(()),
+                    value => {
+                        self.entries.push(Entry { path: path.to_string(), value: value.to_string(), line: self.line });
+                        Some(())
+                    },
+                }
+            },
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.pos += 1;
+        let mut value = String::new();
+        loop {
+            let start = self.pos;
+            while self.peek().is_some_and(|b| !matches!(b, b'"' | b'\\' | b'\n')) {
+                self.pos += 1;
+            }
+            value.push_str(&self.s[start..self.pos]);
+            match self.peek()? {
+                b'"' => {
+                    self.pos += 1;
+                    return Some(value);
+                },
+                b'\n' => return None,
+                _ => {
+                    let escaped = *self.s.as_bytes().get(self.pos + 1)?;
+                    self.pos += 2;
+                    match escaped {
+                        b'n' => value.push('\n'),
+                        b't' => value.push('\t'),
+                        b'r' => value.push('\r'),
+                        b'b' => value.push('\u{8}'),
+                        b'f' => value.push('\u{c}'),
+                        b'u' => value.push(self.unicode()?),
+                        other => value.push(other as char),
+                    };
+                },
+            };
+        }
+    }
+
+    #[inline(always)]
+    fn unicode(&mut self) -> Option<char> {
+        let high = u32::from_str_radix(self.s.get(self.pos..self.pos + 4)?, 16).ok()?;
+        self.pos += 4;
+        if !(0xd800..0xdc00).contains(&high) {
+            return Some(char::from_u32(high).unwrap_or(char::REPLACEMENT_CHARACTER));
+        }
+        let low = self.s.get(self.pos..self.pos + 6)?.strip_prefix("\\u").and_then(|h| u32::from_str_radix(h, 16).ok());
+        match low {
+            Some(low) if (0xdc00..0xe000).contains(&low) => {
+                self.pos += 6;
+                Some(char::from_u32(0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)).unwrap_or(char::REPLACEMENT_CHARACTER))
+            },
+            _ => Some(char::REPLACEMENT_CHARACTER),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+mod ini;
+mod json;
+mod properties;
+mod toml;
+mod xml;
+mod yaml;
+
+#[cfg(test)]
+mod mod_test;
+
+/// Entry is the scalar value of the structured file with the path of keys leading to it and the line the value starts in.
+/// Keys of the path are joined with a dot, indices of sequence items are appended in square brackets, like `servers[0].token`.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub path: String,
+    pub value: String,
+    pub line: usize,
+}
+
+/// Format of the structured file recognised by the name of the file.
+/// Dot env files are parsed as INI files without sections.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Yaml,
+    Toml,
+    Ini,
+    Properties,
+    Xml,
+}
+
+impl Format {
+    #[inline(always)]
+    pub fn detect(file: &str) -> Option<Self> {
+        let name = file.rsplit(['/', '\\']).next().unwrap_or(file).to_lowercase();
+        let extension = name.rsplit_once('.').map(|(_, e)| e).unwrap_or_default();
+        match extension {
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "toml" => Some(Self::Toml),
+            "ini" | "cfg" | "env" => Some(Self::Ini),
+            "properties" => Some(Self::Properties),
+            "xml" | "config" => Some(Self::Xml),
+            _ if name.starts_with(".env") => Some(Self::Ini),
+            _ => None,
+        }
+    }
+
+    /// Parses the content to entries. Parsing is lenient, entries found before the malformed content are returned.
+    ///
+    #[inline(always)]
+    pub fn parse(&self, s: &str) -> Vec<Entry> {
+        match self {
+            Self::Json => json::parse(s),
+            Self::Yaml => yaml::parse(s),
+            Self::Toml => toml::parse(s),
+            Self::Ini => ini::parse(s),
+            Self::Properties => properties::parse(s),
+            Self::Xml => xml::parse(s),
+        }
+    }
+}
+
+#[inline(always)]
+fn join(path: &str, key: &str) -> String {
+    match path.is_empty() {
+        true => key.to_string(),
+        false => format!("{path}.{key}"),
+    }
+}
+
+#[inline(always)]
+fn index(path: &str, idx: usize) -> String {
+    format!("{path}[{idx}]")
+}
+
+/// Removes the matching quotes surrounding the value.
+///
+#[inline(always)]
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if value.len() >= 2 && value.starts_with(quote) && value.ends_with(quote) {
+            return &value[1..value.len() - 1];
+        }
+    }
+    value
+}
+
+/// Cuts the comment starting with one of the markers outside of quotes, the marker shall follow whitespace or start the line.
+///
+#[inline(always)]
+fn strip_comment<'a>(line: &'a str, markers: &[char]) -> &'a str {
+    let mut quote: Option<char> = None;
+    let mut previous = ' ';
+    for (idx, c) in line.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => (),
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if markers.contains(&c) && previous.is_whitespace() => return &line[..idx],
+            None => (),
+        };
+        previous = c;
+    }
+    line
+}
@@ -0,0 +1,132 @@
+use crate::structured::{Entry, Format};
+
+mod tests {
+    use super::*;
+
+    fn entries(format: Format, s: &str) -> Vec<(String, String, usize)> {
+        format.parse(s).into_iter().map(|Entry { path, value, line }| (path, value, line)).collect()
+    }
+
+    fn entry(path: &str, value: &str, line: usize) -> (String, String, usize) {
+        (path.to_string(), value.to_string(), line)
+    }
+
+    #[test]
+    fn it_should_detect_format_by_file_name() {
+        assert_eq!(Format::detect("config/app.JSON"), Some(Format::Json));
+        assert_eq!(Format::detect("deploy.yml"), Some(Format::Yaml));
+        assert_eq!(Format::detect("Cargo.toml"), Some(Format::Toml));
+        assert_eq!(Format::detect("setup.cfg"), Some(Format::Ini));
+        assert_eq!(Format::detect("project/.env.production"), Some(Format::Ini));
+        assert_eq!(Format::detect("application.properties"), Some(Format::Properties));
+        assert_eq!(Format::detect("web.config"), Some(Format::Xml));
+        assert_eq!(Format::detect("src/main.rs"), None);
+        assert_eq!(Format::detect("Makefile"), None);
+    }
+
+    #[test]
+    fn it_should_parse_json() {
+        let s = "{\n  \"db\": {\"password\": \"s3cr\\u0065t\", \"port\": 5432},\n  \"tokens\": [\n    \"abc\",\n    null,\n    {\"key\": \"def\"}\n  ]\n}";
+
+        assert_eq!(
+            entries(Format::Json, s),
+            vec![
+                entry("db.password", "s3cret", 2),
+                entry("db.port", "5432", 2),
+                entry("tokens[0]", "abc", 4),
+                entry("tokens[2].key", "def", 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_return_json_entries_found_before_malformed_content() {
+        assert_eq!(entries(Format::Json, "{\"a\": \"b\", \"c\" \"d\"}"), vec![entry("a", "b", 1)]);
+    }
+
+    #[test]
+    fn it_should_parse_yaml() {
+        let s = "# comment\nservice:\n  name: api # trailing\n  auth:\n    token: 'abc#123'\n  servers:\n    - host: one\n      key: k1\n    - host: two\nlist:\n- a\n- b\ncert: |\n  line one\n  line two\nanchor: &ref !!str value\n";
+
+        assert_eq!(
+            entries(Format::Yaml, s),
+            vec![
+                entry("service.name", "api", 3),
+                entry("service.auth.token", "abc#123", 5),
+                entry("service.servers[0].host", "one", 7),
+                entry("service.servers[0].key", "k1", 8),
+                entry("service.servers[1].host", "two", 9),
+                entry("list[0]", "a", 11),
+                entry("list[1]", "b", 12),
+                entry("cert", "line one\nline two", 14),
+                entry("anchor", "value", 16),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_reset_yaml_paths_on_new_document() {
+        let s = "a:\n  b: 1\n---\nc: 2\n";
+
+        assert_eq!(entries(Format::Yaml, s), vec![entry("a.b", "1", 2), entry("c", "2", 4)]);
+    }
+
+    #[test]
+    fn it_should_parse_toml() {
+        let s = "title = \"app\" # comment\n[database]\npassword = 'p@ss'\nsite.\"a.b\" = true\n[[users]]\ntoken = \"t1\"\n[[users]]\ntoken = \"t2\"\ncreds = { user = \"u\", pass = \"p\" }\nkeys = [\n  \"k1\",\n  \"k2\",\n]\nnote = \"\"\"\nmulti\nline\"\"\"\n";
+
+        assert_eq!(
+            entries(Format::Toml, s),
+            vec![
+                entry("title", "app", 1),
+                entry("database.password", "p@ss", 3),
+                entry("database.site.a.b", "true", 4),
+                entry("users[0].token", "t1", 6),
+                entry("users[1].token", "t2", 8),
+                entry("users[1].creds.user", "u", 9),
+                entry("users[1].creds.pass", "p", 9),
+                entry("users[1].keys[0]", "k1", 11),
+                entry("users[1].keys[1]", "k2", 12),
+                entry("users[1].note", "multi\nline", 14),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_parse_ini_and_dot_env() {
+        let s = "; comment\nroot = top\n[server]\npassword = \"secret\"\nport: 80 ; inline\n";
+
+        assert_eq!(
+            entries(Format::Ini, s),
+            vec![entry("root", "top", 2), entry("server.password", "secret", 4), entry("server.port", "80", 5)]
+        );
+        assert_eq!(
+            entries(Format::Ini, "export API_KEY='abc123'\nURL=postgres://u:p@host/db#frag\n"),
+            vec![entry("API_KEY", "abc123", 1), entry("URL", "postgres://u:p@host/db#frag", 2)]
+        );
+    }
+
+    #[test]
+    fn it_should_parse_properties() {
+        let s = "# comment\n! other\ndb.password=se\\:cret\napi.key : abc\\\n    def\nname value\\u0021\n";
+
+        assert_eq!(
+            entries(Format::Properties, s),
+            vec![entry("db.password", "se:cret", 3), entry("api.key", "abcdef", 4), entry("name", "value!", 6)]
+        );
+    }
+
+    #[test]
+    fn it_should_parse_xml() {
+        let s = "<?xml version=\"1.0\"?>\n<!-- comment -->\n<configuration>\n  <appSettings>\n    <add key=\"ApiKey\" value=\"a&amp;b\"/>\n  </appSettings>\n  <db:password>\n    secret\n  </db:password>\n  <cert><![CDATA[<raw>]]></cert>\n</configuration>\n";
+
+        assert_eq!(
+            entries(Format::Xml, s),
+            vec![
+                entry("configuration.appSettings.ApiKey.value", "a&b", 5),
+                entry("configuration.password", "secret", 8),
+                entry("configuration.cert", "<raw>", 10),
+            ]
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use super::Entry;
+
+/// Parses Java properties file, the key is the path as it is already dotted.
+///
+#[inline(always)]
+pub(super) fn parse(s: &str) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    let mut lines = s.lines().enumerate();
+
+    while let Some((idx, raw)) = lines.next() {
+        let mut logical = raw.trim_start().to_string();
+        if logical.is_empty() || logical.starts_with(['#', '!']) {
+            continue;
+        }
+        while is_continued(&logical) {
+            logical.pop();
+            let Some((_, next)) = lines.next() else {
+                break;
+            };
+            logical.push_str(next.trim_start());
+        }
+        let (key, value) = split_property(&logical);
+        entries.push(Entry { path: unescape(key), value: unescape(value), line: idx + 1 });
+    }
+
+    entries
+}
+
+/// Checks if the line ends with the odd number of backslashes, so the last one continues the line.
+///
+#[inline(always)]
+fn is_continued(line: &str) -> bool {
+    (line.len() - line.trim_end_matches('\\').len()) % 2 == 1
+}
+
+/// Splits the property at the first unescaped `=`, `:` or whitespace.
+///
+#[inline(always)]
+fn split_property(line: &str) -> (&str, &str) {
+    let mut escaped = false;
+    for (idx, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '=' | ':' => return (&line[..idx], line[idx + 1..].trim_start()),
+            c if c.is_whitespace() => {
+                let rest = line[idx..].trim_start();
+                let rest = rest.strip_prefix(['=', ':']).unwrap_or(rest);
+                return (&line[..idx], rest.trim_start());
+            },
+            _ => (),
+        };
+    }
+    (line, "")
+}
+
+#[inline(always)]
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => result.push('\t'),
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('f') => result.push('\u{c}'),
+            Some('u') => {
+                let digits: String = chars.by_ref().take(4).collect();
+                let c = u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32);
+                result.push(c.unwrap_or(char::REPLACEMENT_CHARACTER));
+            },
+            Some(other) => result.push(other),
+            None => (),
+        };
+    }
+
+    result
+}
@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+use super::{index, join, strip_comment, unquote, Entry};
+
+const MULTILINE_DELIMITERS: [&str; 2] = ["\"\"\"", "'''"];
+
+/// Parses TOML document with tables, arrays of tables, dotted keys, inline tables and arrays.
+///
+#[inline(always)]
+pub(super) fn parse(s: &str) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    let mut table = String::new();
+    let mut arrays: HashMap<String, usize> = HashMap::new();
+    let mut lines = s.lines().enumerate();
+
+    while let Some((idx, raw)) = lines.next() {
+        let line = idx + 1;
+        let content = strip_comment(raw, &['#']).trim();
+        if content.is_empty() {
+            continue;
+        }
+        if let Some(name) = content.strip_prefix("[[").and_then(|c| c.strip_suffix("]]")) {
+            let path = key_path("", name);
+            let counter = arrays.entry(path.clone()).or_insert(0);
+            table = index(&path, *counter);
+            *counter += 1;
+            continue;
+        }
+        if let Some(name) = content.strip_prefix('[').and_then(|c| c.strip_suffix(']')) {
+            table = key_path("", name);
+            continue;
+        }
+        let Some((key, value)) = split_assignment(content) else {
+            continue;
+        };
+        let mut value = value.to_string();
+        for delimiter in MULTILINE_DELIMITERS {
+            if value.starts_with(delimiter) && !value[delimiter.len()..].contains(delimiter) {
+                for (_, next) in lines.by_ref() {
+                    value.push('\n');
+                    value.push_str(next);
+                    if next.contains(delimiter) {
+                        break;
+                    }
+                }
+            }
+        }
+        if value.starts_with('[') {
+            while !is_balanced(&value) {
+                let Some((_, next)) = lines.next() else {
+                    break;
+                };
+                value.push('\n');
+                value.push_str(strip_comment(next, &['#']).trim());
+            }
+        }
+        let mut parser = Parser { s: &value, pos: 0, line, entries: &mut entries };
+        let _ = parser.value(&key_path(&table, key));
+    }
+
+    entries
+}
+
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+    line: usize,
+    entries: &'a mut Vec<Entry>,
+}
+
+impl Parser<'_> {
+    #[inline(always)]
+    fn rest(&self) -> &str {
+        &self.s[self.pos..]
+    }
+
+    #[inline(always)]
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    #[inline(always)]
+    fn push(&mut self, path: &str, value: String, start: usize) {
+        let line = self.line + self.s[..start].matches('\n').count();
+        self.entries.push(Entry { path: path.to_string(), value, line });
+    }
+
+    fn value(&mut self, path: &str) -> Option<()> {
+        self.skip_whitespace();
+        let start = self.pos;
+        for delimiter in MULTILINE_DELIMITERS {
+            if let Some(rest) = self.rest().strip_prefix(delimiter) {
+                let end = rest.find(delimiter)?;
+                let raw = rest[..end].strip_prefix('\n').unwrap_or(&rest[..end]);
+                let value = if delimiter.starts_with('"') { unescape(raw) } else { raw.to_string() };
+                self.pos += delimiter.len() * 2 + end;
+                self.push(path, value, start);
+                return Some(());
+            }
+        }
+        match self.rest().chars().next()? {
+            '"' => {
+                let end = string_end(self.rest())?;
+                let value = unescape(&self.rest()[1..end]);
+                self.pos += end + 1;
+                self.push(path, value, start);
+            },
+            '\'' => {
+                let end = self.rest()[1..].find('\'')? + 1;
+                let value = self.rest()[1..end].to_string();
+                self.pos += end + 1;
+                self.push(path, value, start);
+            },
+            '[' => {
+                self.pos += 1;
+                let mut idx = 0;
+                loop {
+                    self.skip_whitespace();
+                    match self.rest().chars().next()? {
+                        ']' => {
+                            self.pos += 1;
+                            break;
+                        },
+                        ',' => self.pos += 1,
+                        _ => {
+                            self.value(&index(path, idx))?;
+                            idx += 1;
+                        },
+                    };
+                }
+            },
+            '{' => {
+                self.pos += 1;
+                loop {
+                    self.skip_whitespace();
+                    match self.rest().chars().next()? {
+                        '}' => {
+                            self.pos += 1;
+                            break;
+                        },
+                        ',' => self.pos += 1,
+                        _ => {
+                            let eq = self.rest().find('=')?;
+                            let key = key_path(path, &self.rest()[..eq]);
+                            self.pos += eq + 1;
+                            self.value(&key)?;
+                        },
+                    };
+                }
+            },
+            _ => {
+                let rest = self.rest();
+                let end = rest.find([',', ']', '}', '\n']).unwrap_or(rest.len());
+                let value = rest[..end].trim().to_string();
+                if value.is_empty() {
+                    return None;
+                }
+                self.pos += end;
+                self.push(path, value, start);
+            },
+        };
+
+        Some(())
+    }
+}
+
+/// Joins the table path with the dotted key, quoted parts of the key may contain dots.
+///
+#[inline(always)]
+fn key_path(table: &str, key: &str) -> String {
+    let mut path = table.to_string();
+    let mut quote: Option<char> = None;
+    let mut start = 0;
+    for (idx, c) in key.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => (),
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '.' => {
+                path = join(&path, unquote(key[start..idx].trim()));
+                start = idx + 1;
+            },
+            None => (),
+        };
+    }
+
+    join(&path, unquote(key[start..].trim()))
+}
+
+#[inline(always)]
+fn split_assignment(content: &str) -> Option<(&str, &str)> {
+    let mut quote: Option<char> = None;
+    for (idx, c) in content.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => (),
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '=' => return Some((content[..idx].trim(), content[idx + 1..].trim())),
+            None => (),
+        };
+    }
+    None
+}
+
+/// Checks if square brackets outside of strings are balanced.
+///
+#[inline(always)]
+fn is_balanced(value: &str) -> bool {
+    let mut depth = 0i32;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for c in value.chars() {
+        match quote {
+            Some('"') if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => (),
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '[' => depth += 1,
+            None if c == ']' => depth -= 1,
+            None => (),
+        };
+    }
+    depth <= 0
+}
+
+/// Returns the index of the quote closing the basic string starting at the beginning of the value.
+///
+#[inline(always)]
+fn string_end(value: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (idx, c) in value.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some(idx),
+            _ => (),
+        };
+    }
+    None
+}
+
+#[inline(always)]
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some('b') => result.push('\u{8}'),
+            Some('f') => result.push('\u{c}'),
+            Some(u @ ('u' | 'U')) => {
+                let digits: String = chars.by_ref().take(if u == 'u' { 4 } else { 8 }).collect();
+                let c = u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32);
+                result.push(c.unwrap_or(char::REPLACEMENT_CHARACTER));
+            },
+            Some('\n') => {
+                while chars.clone().next().is_some_and(char::is_whitespace) {
+                    chars.next();
+                }
+            },
+            Some(other) => result.push(other),
+            None => (),
+        };
+    }
+
+    result
+}
@@ -0,0 +1,180 @@
+use super::{join, Entry};
+
+/// Attributes naming the element, their value is taken as the path segment instead of the tag name.
+///
+const NAMING_ATTRIBUTES: [&str; 2] = ["key", "name"];
+
+/// Parses XML document, text of the element is the value of the element path and attributes are values of `element.attribute` paths.
+/// Elements having the `key` or `name` attribute are named after the attribute, like `<add key="ApiKey" value="..."/>` gives `ApiKey.value`.
+///
+#[inline(always)]
+pub(super) fn parse(s: &str) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    let mut stack: Vec<String> = Vec::new();
+    let mut lines = Lines { s, counted: 0, line: 1 };
+    let mut pos = 0;
+
+    while pos < s.len() {
+        let Some(offset) = s[pos..].find('<') else {
+            break;
+        };
+        let text = &s[pos..pos + offset];
+        if let (false, Some(path)) = (text.trim().is_empty(), stack.last()) {
+            let start = pos + text.len() - text.trim_start().len();
+            entries.push(Entry { path: path.clone(), value: unescape(text.trim()), line: lines.at(start) });
+        }
+        let start = pos + offset;
+        let rest = &s[start..];
+        if rest.starts_with("<!--") {
+            pos = start + rest.find("-->").map(|e| e + 3).unwrap_or(rest.len());
+            continue;
+        }
+        if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let end = cdata.find("]]>").unwrap_or(cdata.len());
+            if let (false, Some(path)) = (cdata[..end].trim().is_empty(), stack.last()) {
+                entries.push(Entry { path: path.clone(), value: cdata[..end].trim().to_string(), line: lines.at(start) });
+            }
+            pos = (start + 9 + end + 3).min(s.len());
+            continue;
+        }
+        if rest.starts_with("<?") || rest.starts_with("<!") {
+            pos = start + rest.find('>').map(|e| e + 1).unwrap_or(rest.len());
+            continue;
+        }
+        let Some(end) = tag_end(rest) else {
+            break;
+        };
+        pos = start + end + 1;
+        let tag = &rest[1..end];
+        if tag.starts_with('/') {
+            stack.pop();
+            continue;
+        }
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+        let name_len = tag.find(char::is_whitespace).unwrap_or(tag.len());
+        let name = &tag[..name_len];
+        let attributes = attributes(&tag[name_len..]);
+        let segment = attributes
+            .iter()
+            .find(|(n, _, _)| NAMING_ATTRIBUTES.contains(n))
+            .map(|(_, v, _)| unescape(v))
+            .unwrap_or_else(|| name.rsplit(':').next().unwrap_or(name).to_string());
+        let path = join(stack.last().map(String::as_str).unwrap_or_default(), &segment);
+        for (attribute, value, offset) in attributes {
+            if NAMING_ATTRIBUTES.contains(&attribute) {
+                continue;
+            }
+            let line = lines.at(start + 1 + name_len + offset);
+            entries.push(Entry { path: join(&path, attribute), value: unescape(value), line });
+        }
+        if !self_closing {
+            stack.push(path);
+        }
+    }
+
+    entries
+}
+
+/// Lines counts new lines incrementally as offsets are requested in increasing order.
+///
+struct Lines<'a> {
+    s: &'a str,
+    counted: usize,
+    line: usize,
+}
+
+impl Lines<'_> {
+    #[inline(always)]
+    fn at(&mut self, offset: usize) -> usize {
+        if offset > self.counted {
+            self.line += self.s[self.counted..offset].matches('\n').count();
+            self.counted = offset;
+        }
+        self.line
+    }
+}
+
+/// Returns the index of `>` closing the tag, quoted attribute values may contain `>`.
+///
+#[inline(always)]
+fn tag_end(tag: &str) -> Option<usize> {
+    let mut quote: Option<char> = None;
+    for (idx, c) in tag.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => (),
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '>' => return Some(idx),
+            None => (),
+        };
+    }
+    None
+}
+
+/// Returns the name, the raw value and the offset of the value of each attribute.
+///
+#[inline(always)]
+fn attributes(s: &str) -> Vec<(&str, &str, usize)> {
+    let mut result = Vec::new();
+    let mut rest = s;
+    loop {
+        let trimmed = rest.trim_start();
+        let Some(eq) = trimmed.find('=') else {
+            break;
+        };
+        let name = trimmed[..eq].trim();
+        let after = trimmed[eq + 1..].trim_start();
+        let Some(quote) = after.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            break;
+        };
+        let Some(end) = after[1..].find(quote) else {
+            break;
+        };
+        result.push((name.rsplit(':').next().unwrap_or(name), &after[1..end + 1], s.len() - after.len() + 1));
+        rest = &after[end + 2..];
+    }
+
+    result
+}
+
+#[inline(always)]
+fn unescape(value: &str) -> String {
+    if !value.contains('&') {
+        return value.to_string();
+    }
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let c = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => match entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                None => entity.strip_prefix('#').and_then(|d| d.parse().ok()).and_then(char::from_u32),
+            },
+        };
+        match c {
+            Some(c) => {
+                result.push(c);
+                rest = &rest[end + 1..];
+            },
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            },
+        };
+    }
+    result.push_str(rest);
+
+    result
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use super::{index, join, strip_comment, unquote, Entry};
+
+#[derive(Debug, PartialEq, Eq)]
+enum Kind {
+    Key,
+    Item,
+}
+
+/// Frame is the mapping key or the sequence item holding nested values, indent is the column it starts at.
+///
+#[derive(Debug)]
+struct Frame {
+    indent: usize,
+    path: String,
+    kind: Kind,
+}
+
+/// Block is the literal or folded block scalar, lines indented more than the key belong to it.
+///
+#[derive(Debug)]
+struct Block {
+    path: String,
+    line: usize,
+    indent: usize,
+    folded: bool,
+    lines: Vec<String>,
+}
+
+impl Block {
+    #[inline(always)]
+    fn entry(self) -> Entry {
+        let separator = if self.folded { " " } else { "\n" };
+        Entry { path: self.path, value: self.lines.join(separator).trim_end().to_string(), line: self.line }
+    }
+}
+
+/// Parses block style YAML documents, flow collections are taken as values and multi-line flow scalars are skipped.
+///
+#[inline(always)]
+pub(super) fn parse(s: &str) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut items: HashMap<String, usize> = HashMap::new();
+    let mut block: Option<Block> = None;
+
+    for (idx, raw) in s.lines().enumerate() {
+        let line = idx + 1;
+        let mut indent = raw.len() - raw.trim_start_matches(' ').len();
+        if let Some(b) = &mut block {
+            if raw.trim().is_empty() || indent > b.indent {
+                b.lines.push(raw.trim().to_string());
+                continue;
+            }
+            if let Some(b) = block.take() {
+                entries.push(b.entry());
+            }
+        }
+        let mut content = strip_comment(raw, &['#']).trim();
+        if content.is_empty() {
+            continue;
+        }
+        if content == "---" || content == "..." || content.starts_with("--- ") {
+            stack.clear();
+            items.clear();
+            continue;
+        }
+        loop {
+            let is_item = content == "-" || content.starts_with("- ");
+            while let Some(top) = stack.last() {
+                match top.indent > indent || (top.indent == indent && (!is_item || top.kind == Kind::Item)) {
+                    true => stack.pop(),
+                    false => break,
+                };
+            }
+            if !is_item {
+                break;
+            }
+            let parent = stack.last().map(|f| f.path.as_str()).unwrap_or_default().to_string();
+            let counter = items.entry(parent.clone()).or_insert(0);
+            stack.push(Frame { indent, path: index(&parent, *counter), kind: Kind::Item });
+            *counter += 1;
+            let rest = content[1..].trim_start();
+            indent += content.len() - rest.len();
+            content = rest;
+            if content.is_empty() {
+                break;
+            }
+        }
+        if content.is_empty() {
+            continue;
+        }
+        let parent = stack.last().map(|f| f.path.as_str()).unwrap_or_default();
+        let Some((key, value)) = split_key(content) else {
+            entries.push(Entry { path: parent.to_string(), value: unquote(strip_properties(content)).to_string(), line });
+            continue;
+        };
+        let path = join(parent, key);
+        let value = strip_properties(value);
+        match value.chars().next() {
+            None => stack.push(Frame { indent, path, kind: Kind::Key }),
+            Some('|') | Some('>') => block = Some(Block { path, line: line + 1, indent, folded: value.starts_with('>'), lines: Vec::new() }),
+            Some(_) => entries.push(Entry { path, value: unquote(value).to_string(), line }),
+        };
+    }
+    if let Some(b) = block {
+        entries.push(b.entry());
+    }
+
+    entries
+}
+
+/// Splits the mapping entry to the key and the value, returns None if the content isn't the mapping entry.
+///
+#[inline(always)]
+fn split_key(content: &str) -> Option<(&str, &str)> {
+    if content.starts_with(['"', '\'']) {
+        let quote = content.chars().next()?;
+        let end = content[1..].find(quote)? + 1;
+        let value = content[end + 1..].trim_start().strip_prefix(':')?;
+        if !value.is_empty() && !value.starts_with([' ', '\t']) {
+            return None;
+        }
+        return Some((&content[1..end], value.trim()));
+    }
+    if content.starts_with(['[', '{']) {
+        return None;
+    }
+    let idx = content.find(": ").or_else(|| content.strip_suffix(':').map(|k| k.len()))?;
+
+    Some((content[..idx].trim_end(), content[idx + 1..].trim()))
+}
+
+/// Removes anchors and tags preceding the value.
+///
+#[inline(always)]
+fn strip_properties(mut value: &str) -> &str {
+    while value.starts_with(['&', '!']) {
+        value = value.split_once(' ').map(|(_, v)| v.trim_start()).unwrap_or_default();
+    }
+    value
+}